@@ -1,10 +1,20 @@
 use super::grid::ctx2d::*;
 use super::grid::ds::*;
+use super::grid::events::*;
+use super::grid::layout::*;
+use super::grid::locale::*;
+use super::grid::measure::*;
+use super::grid::pointer::*;
 use super::grid::renderer::*;
 use super::grid::schema::*;
+use super::grid::selection::*;
+use super::grid::theme::*;
+use super::grid::validate::*;
+use crate::error::*;
 use crate::grid::column::*;
 
 use crate::utils::*;
+use std::cell::RefCell;
 use std::f64;
 use wasm_bindgen::prelude::*;
 
@@ -24,30 +34,36 @@ enum Side {
     Ask = 1,
 }
 
-const CUM_SIZE_COL_ID: &str = "cumSize"; // don't change it - used by the UI demo
-
 #[wasm_bindgen]
 #[derive(Default)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct DOB {
     id: String,
     bid_schema: Schema,
     ask_schema: Schema,
     top_index: usize,
+    locale: Locale,
+    theme: Theme,
+    measure_cache: RefCell<MeasureCache>,
+    headers: Vec<HeaderLayout>, // bid and ask panels of the last render, for clicks
+    windows: Vec<DataWindow>,   // bid and ask rows of the last render
+    callbacks: Callbacks,
 }
 
 #[wasm_bindgen]
 impl DOB {
-    pub fn new(id: String, schema_obj: &JsValue) -> DOB {
+    pub fn new(id: String, schema_obj: &JsValue) -> Result<DOB, JsValue> {
         set_panic_hook();
-        let (bid_schema, ask_schema) = DOB::set_schema(schema_obj);
-        DOB {
+        let (bid_schema, ask_schema) = DOB::set_schema(schema_obj)?;
+        Ok(DOB {
             id,
             bid_schema,
             ask_schema,
             ..Default::default()
-        }
+        })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &mut self,
         bids: &[SZ],
@@ -57,11 +73,24 @@ impl DOB {
         top: u32,
         width: u32,
         height: u32,
-    ) {
-        let ctx = &ctx(&self.id);
+    ) -> Result<(), JsValue> {
+        let bids = validate_layout(&self.bid_schema, bids, data_width).map_err(GridError::from)?;
+        let asks = validate_layout(&self.bid_schema, asks, data_width).map_err(GridError::from)?;
+        let cum_col = self
+            .bid_schema
+            .get_col_by_id(CUM_SIZE_COL_ID)
+            .ok_or_else(|| GridError::missing_column(CUM_SIZE_COL_ID))?;
+        let ctx = &ctx(&self.id)?;
+        let format = FormatContext {
+            locale: &self.locale,
+            ..Default::default()
+        };
 
         let mut bid_panel = (
-            GridRenderer::new(ctx, &self.bid_schema, left, top, width / 2, height, 0),
+            GridRenderer::new(ctx, &self.bid_schema, left, top, width / 2, height, 0)
+                .with_format(format)
+                .with_theme(&self.theme)
+                .with_measure_cache(&self.measure_cache),
             DataSource::new(bids, data_width, None),
             Side::Bid,
         );
@@ -74,7 +103,10 @@ impl DOB {
                 width / 2,
                 height,
                 0,
-            ),
+            )
+            .with_format(format)
+            .with_theme(&self.theme)
+            .with_measure_cache(&self.measure_cache),
             DataSource::new(asks, data_width, None),
             Side::Ask,
         );
@@ -83,9 +115,10 @@ impl DOB {
             &bid_panel.1,
             &ask_panel.1,
             bid_panel.0.client_width(),
-            self.bid_schema.get_col_by_id(CUM_SIZE_COL_ID).unwrap(),
+            cum_col,
         );
 
+        let (mut headers, mut windows) = (Vec::with_capacity(2), Vec::with_capacity(2));
         for (grid, ds, side) in [&mut bid_panel, &mut ask_panel].iter_mut() {
             grid.set_top_index(self.top_index);
             grid.calc_col_width();
@@ -93,30 +126,130 @@ impl DOB {
 
             grid.clip_begin();
             grid.render_data(ds);
-            self.render_pyramid(grid, ds, *side, ratio);
+            self.render_pyramid(grid, ds, *side, ratio, cum_col);
             grid.render_header();
             grid.clip_end();
+            headers.push(grid.header_layout());
+            let rows = self.top_index..self.top_index + grid.visible_row_count();
+            // both sides share the buffer layout
+            windows.push(DataWindow::capture(&self.bid_schema.cols, ds, rows));
+        }
+        self.headers = headers;
+        self.windows = windows;
+        Ok(())
+    }
+
+    /// Calls `callback` with `{ row, col, value, side }` on `CellClick` and
+    /// `CellDoubleClick`, `row` being the level index and `side` "bid" or "ask".
+    /// The book raises no other events. Callbacks run as microtasks, see `Grid.on`.
+    pub fn on(&mut self, kind: GridEventKind, callback: Option<js_sys::Function>) {
+        self.callbacks.set(kind, callback);
+    }
+
+    /// True when a cell was hit.
+    pub fn pointer_down(&self, x: f64, y: f64) -> Result<bool, JsValue> {
+        match self.cell_event(x, y) {
+            Some(cell) => {
+                self.callbacks.emit(&GridEvent::CellClick(cell))?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// True when a cell was hit.
+    pub fn double_click(&self, x: f64, y: f64) -> Result<bool, JsValue> {
+        match self.cell_event(x, y) {
+            Some(cell) => {
+                self.callbacks.emit(&GridEvent::CellDoubleClick(cell))?;
+                Ok(true)
+            }
+            None => Ok(false),
         }
     }
 
-    fn set_schema(obj: &JsValue) -> (Schema, Schema) {
-        console_error_panic_hook::set_once();
-        let mut bid_schema = obj.into_serde::<Schema>().unwrap();
-        normalize_schema(&mut bid_schema);
+    fn set_schema(obj: &JsValue) -> GridResult<(Schema, Schema)> {
+        let mut bid_schema: Schema = from_js(obj)?;
+        normalize_schema(&mut bid_schema)?;
+        if bid_schema.get_col_by_id(CUM_SIZE_COL_ID).is_none() {
+            return Err(GridError::missing_column(CUM_SIZE_COL_ID));
+        }
         // flip the ask side columns and alignments
         let mut ask_schema = bid_schema.clone();
-        ask_schema.cols.reverse();
-        for mut col in &mut ask_schema.cols {
+        ask_schema.order.reverse();
+        for col in &mut ask_schema.cols {
             col.align = "left".to_string();
         }
-        (bid_schema, ask_schema)
+        Ok((bid_schema, ask_schema))
+    }
+    /// Problems found in the current schema, see `validate_schema`.
+    pub fn validate_schema(&self) -> Result<JsValue, JsValue> {
+        problems_to_js(&validate_schema(
+            &self.bid_schema,
+            &ValidateOptions {
+                required_cols: &[CUM_SIZE_COL_ID],
+                ..Default::default()
+            },
+        ))
     }
+
     pub fn set_top_index(&mut self, top_index: usize) {
         self.top_index = top_index;
     }
+
+    /// Sets separators and date names, e.g. "de-DE" renders 1.234,56.
+    pub fn set_locale(&mut self, tag: &str) -> Result<(), JsValue> {
+        self.locale =
+            Locale::find(tag).map_err(|e| GridError::new(ErrorKind::InvalidArgument, e))?;
+        Ok(())
+    }
+
+    pub fn get_locale(&self) -> String {
+        self.locale.tag.to_string()
+    }
+
+    /// Takes "dark", "light" or a theme object, missing colors keep the dark preset.
+    pub fn set_theme(&mut self, obj: &JsValue) -> Result<(), JsValue> {
+        self.theme = theme_from_js(obj)?;
+        self.measure_cache.borrow_mut().clear();
+        Ok(())
+    }
+
+    pub fn get_theme(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.theme).map_err(GridError::from)?)
+    }
 }
 
 impl DOB {
+    fn cell_event(&self, x: f64, y: f64) -> Option<CellEvent> {
+        let (side, (row, col)) = self
+            .headers
+            .iter()
+            .enumerate()
+            .find_map(|(side, header)| Some((side, header.data_cell_at(x, y)?)))?;
+        let schema = match side {
+            0 => &self.bid_schema,
+            _ => &self.ask_schema,
+        };
+        let id = self.headers[side].ids.get(col)?;
+        let column = schema.get_col_by_id(id)?;
+        let window = self.windows.get(side)?;
+        let ds = window.data_source(None, None);
+        if row >= ds.row_count {
+            return None;
+        }
+        let key = match schema.get_key_col() {
+            Some(key) => ds.row_key(row, Some(key))?,
+            None => RowKey::Number((window.first_row + row) as f64), // level index
+        };
+        Some(CellEvent {
+            row: key,
+            col: id.to_string(),
+            value: cell_value(&ds, row, column),
+            side: Some(if side == 0 { "bid" } else { "ask" }),
+        })
+    }
+
     fn calc_bid_ask_ratio(
         &self,
         left_ds: &DataSource,
@@ -136,27 +269,36 @@ impl DOB {
         }
     }
 
-    fn render_pyramid(&self, gr: &GridRenderer, ds: &DataSource, side: Side, ratio: f64) {
+    fn render_pyramid(
+        &self,
+        gr: &GridRenderer,
+        ds: &DataSource,
+        side: Side,
+        ratio: f64,
+        cum_col: &Column,
+    ) {
         if ds.row_count == 0 {
             return;
         }
-        let cum_col = gr.schema.unwrap().get_col_by_id(CUM_SIZE_COL_ID).unwrap();
         let ctx = gr.get_ctx();
         ctx.save();
 
         for row in 0_usize.. {
-            let y = gr.top() + ((row + HEADER_LINES) * gr.row_height) as f64;
+            let y = gr.top() + ((row + gr.header_lines()) * gr.row_height) as f64;
             let index = self.top_index + row;
             if y < gr.bottom() && index < ds.row_count {
-                let len = ds.get_value_f64(index, cum_col).unwrap_or_default() * ratio;
+                let len = match ds.get_value_f64(index, cum_col) {
+                    Some(v) => v * ratio,
+                    None => continue,
+                };
                 let x = match side {
                     Side::Bid => gr.right() - len,
                     Side::Ask => gr.left(),
                 };
 
                 let color = match side {
-                    Side::Bid => "#0c433899",
-                    Side::Ask => "#ff3b6960",
+                    Side::Bid => &gr.theme().bid_bar,
+                    Side::Ask => &gr.theme().ask_bar,
                 };
 
                 fill_rect(ctx, x, y, len, gr.row_height as f64, color);
@@ -169,6 +311,6 @@ impl DOB {
     }
 
     fn get_max_cum_size(&self, ds: &DataSource, col: &Column) -> f64 {
-        ds.get_value_f64(ds.row_count - 1, col).unwrap_or_default()
+        ds.max_f64(col).unwrap_or_default()
     }
 }
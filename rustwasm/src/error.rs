@@ -0,0 +1,129 @@
+use crate::grid::layout::LayoutError;
+use js_sys::{Error, Reflect};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ErrorKind {
+    InvalidSchema,
+    InvalidData,
+    InvalidArgument,
+    MissingColumn,
+    MissingElement,
+    Canvas,
+}
+
+impl ErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::InvalidSchema => "InvalidSchema",
+            ErrorKind::InvalidData => "InvalidData",
+            ErrorKind::InvalidArgument => "InvalidArgument",
+            ErrorKind::MissingColumn => "MissingColumn",
+            ErrorKind::MissingElement => "MissingElement",
+            ErrorKind::Canvas => "Canvas",
+        }
+    }
+}
+
+/// Error returned by every exported method. Converted into a JS `Error`
+/// carrying `kind`, `column` and `offset` properties so the UI can display it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GridError {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub column: Option<String>,
+    pub offset: Option<usize>,
+}
+
+pub type GridResult<T> = Result<T, GridError>;
+
+impl GridError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> GridError {
+        GridError {
+            kind,
+            message: message.into(),
+            column: None,
+            offset: None,
+        }
+    }
+
+    pub fn schema(message: impl Into<String>) -> GridError {
+        GridError::new(ErrorKind::InvalidSchema, message)
+    }
+
+    pub fn missing_column(id: &str) -> GridError {
+        GridError::new(
+            ErrorKind::MissingColumn,
+            format!("column '{}' not found", id),
+        )
+        .with_column(id)
+    }
+
+    pub fn with_column(mut self, id: &str) -> GridError {
+        self.column = Some(id.to_string());
+        self
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> GridError {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.kind.as_str(), self.message)?;
+        if let Some(column) = &self.column {
+            write!(f, " (column '{}'", column)?;
+            match self.offset {
+                Some(offset) => write!(f, ", offset {})", offset)?,
+                None => write!(f, ")")?,
+            }
+        } else if let Some(offset) = self.offset {
+            write!(f, " (offset {})", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for GridError {}
+
+impl From<LayoutError> for GridError {
+    fn from(e: LayoutError) -> GridError {
+        let err = GridError::new(ErrorKind::InvalidData, e.to_string());
+        match e {
+            LayoutError::BufferTooSmall { len, .. } | LayoutError::UnalignedBuffer { len, .. } => {
+                err.with_offset(len)
+            }
+            _ => err,
+        }
+    }
+}
+
+impl From<serde_wasm_bindgen::Error> for GridError {
+    fn from(e: serde_wasm_bindgen::Error) -> GridError {
+        GridError::new(ErrorKind::InvalidArgument, e.to_string())
+    }
+}
+
+impl From<GridError> for JsValue {
+    fn from(e: GridError) -> JsValue {
+        let err = Error::new(&e.to_string());
+        err.set_name("GridError");
+        let set = |key: &str, value: JsValue| {
+            let _ = Reflect::set(&err, &JsValue::from_str(key), &value);
+        };
+        set("kind", JsValue::from_str(e.kind.as_str()));
+        set(
+            "column",
+            e.column.as_deref().map_or(JsValue::NULL, JsValue::from_str),
+        );
+        set(
+            "offset",
+            e.offset
+                .map_or(JsValue::NULL, |o| JsValue::from_f64(o as f64)),
+        );
+        err.into()
+    }
+}
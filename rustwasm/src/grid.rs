@@ -1,7 +1,26 @@
+pub mod autofit;
+pub mod clipboard;
 pub mod column;
 pub mod ctrl;
 pub mod ctx2d;
+pub mod datefmt;
+pub mod decimal;
 pub mod ds;
+pub mod events;
+pub mod export;
+pub mod header;
+pub mod layout;
+pub mod locale;
+pub mod measure;
+pub mod numfmt;
+pub mod pointer;
+pub mod price;
 pub mod renderer;
 pub mod schema;
+pub mod selection;
 pub mod sparkline;
+pub mod state;
+pub mod symbols;
+pub mod theme;
+pub mod tooltip;
+pub mod validate;
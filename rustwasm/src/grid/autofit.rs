@@ -0,0 +1,69 @@
+use crate::grid::column::*;
+use crate::grid::ctx2d::PADDING_H;
+use crate::grid::ds::*;
+use crate::grid::schema::*;
+use crate::grid::theme::*;
+use wasm_bindgen::prelude::*;
+
+pub const SAMPLE_ROWS: usize = 1000;
+pub const MIN_FIT_WIDTH: f64 = 40.0;
+pub const MAX_FIT_WIDTH: f64 = 480.0;
+
+/// Rows measured by `Grid::auto_fit_columns`.
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum FitMode {
+    Visible = 0, // rows in view
+    Sample = 1,  // up to SAMPLE_ROWS spread over the whole data source
+}
+
+pub fn fit_rows(
+    mode: FitMode,
+    row_count: usize,
+    top_index: usize,
+    visible_rows: usize,
+) -> Vec<usize> {
+    match mode {
+        FitMode::Visible => {
+            let start = std::cmp::min(top_index, row_count);
+            (start..std::cmp::min(start + visible_rows, row_count)).collect()
+        }
+        FitMode::Sample if row_count <= SAMPLE_ROWS => (0..row_count).collect(),
+        // in u64, i * row_count overflows a 32-bit usize past ~4.3M rows
+        FitMode::Sample => (0..SAMPLE_ROWS)
+            .map(|i| (i as u64 * row_count as u64 / SAMPLE_ROWS as u64) as usize)
+            .collect(),
+    }
+}
+
+/// Width per schema column fitting its header and the formatted cells of `rows`,
+/// `None` for hidden and sparkline columns. `measure` takes the text and the font.
+pub fn fit_widths(
+    schema: &Schema,
+    ds: &DataSource,
+    rows: &[usize],
+    fc: &FormatContext,
+    theme: &Theme,
+    measure: &mut dyn FnMut(&str, &str) -> f64,
+) -> Vec<Option<f64>> {
+    schema
+        .cols
+        .iter()
+        .map(|col| {
+            if col.hidden || col.col_type == ColumnType::Sparkline {
+                return None;
+            }
+            // emphasized price digits are bold, measure the whole price bold
+            let bold = col.price.as_ref().is_some_and(|p| p.big_figure_digits > 0);
+            let font = theme.cell_font(col, bold);
+            let cells = rows
+                .iter()
+                .filter_map(|&row| ds.format_cell(row, col, fc, &schema.null_text))
+                .map(|text| measure(&text, &font))
+                .fold(0.0, f64::max);
+            let header = measure(&col.name, &theme.header_font);
+            let width = (cells.max(header) + 2.0 * PADDING_H).ceil();
+            Some(width.clamp(MIN_FIT_WIDTH, MAX_FIT_WIDTH))
+        })
+        .collect()
+}
@@ -0,0 +1,164 @@
+use crate::grid::column::*;
+use crate::grid::ds::*;
+use crate::grid::schema::*;
+use crate::grid::selection::*;
+use std::borrow::Cow;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ClipFormat {
+    Tsv = 0, // pastes into Excel as cells
+    Csv = 1, // RFC 4180
+    Html = 2,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ClipOptions {
+    pub format: ClipFormat,
+    pub raw: bool,    // stored values instead of the displayed text
+    pub header: bool, // column names first
+}
+
+/// Rows and visible column indexes covered by a selection. Whole rows added with
+/// ctrl take every visible column, otherwise the range columns are used.
+pub fn selected_cells(resolved: &ResolvedSelection, col_count: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut rows = resolved.rows.clone();
+    if let Some(((r0, r1), _)) = resolved.range {
+        rows.extend(r0..=r1);
+    }
+    rows.sort_unstable();
+    rows.dedup();
+    let cols = match resolved.range {
+        Some((_, (c0, c1))) if resolved.rows.is_empty() => {
+            (c0..=c1.min(col_count.saturating_sub(1))).collect()
+        }
+        _ => (0..col_count).collect(),
+    };
+    (rows, cols)
+}
+
+/// Text of one cell for export, `null_text` for nulls unless raw.
+pub fn cell_text<'t>(
+    ds: &DataSource<'t>,
+    row: usize,
+    col: &Column,
+    fc: &FormatContext,
+    raw: bool,
+    null_text: &'t str,
+) -> Cow<'t, str> {
+    let text = if raw {
+        ds.raw_cell(row, col)
+    } else {
+        ds.format_cell(row, col, fc, null_text)
+    };
+    text.unwrap_or(Cow::Borrowed(""))
+}
+
+/// The selected cells as one clipboard string.
+pub fn copy_cells(
+    schema: &Schema,
+    ds: &DataSource,
+    resolved: &ResolvedSelection,
+    fc: &FormatContext,
+    options: ClipOptions,
+) -> String {
+    let visible: Vec<&Column> = schema.get_visible_cols().collect();
+    let (rows, cols) = selected_cells(resolved, visible.len());
+    if rows.is_empty() || cols.is_empty() {
+        return String::new();
+    }
+    let cols: Vec<&Column> = cols.iter().map(|&i| visible[i]).collect();
+    let null_text = if options.raw { "" } else { &schema.null_text };
+    let mut out = String::new();
+    if options.format == ClipFormat::Html {
+        out.push_str("<table>");
+    }
+    if options.header {
+        write_row(
+            &mut out,
+            options.format,
+            true,
+            cols.iter().map(|c| Cow::Borrowed(c.name.as_str())),
+        );
+    }
+    for row in rows {
+        let cells = cols
+            .iter()
+            .map(|col| cell_text(ds, row, col, fc, options.raw, null_text));
+        write_row(&mut out, options.format, false, cells);
+    }
+    if options.format == ClipFormat::Html {
+        out.push_str("</table>");
+    }
+    out
+}
+
+fn write_row<'t>(
+    out: &mut String,
+    format: ClipFormat,
+    header: bool,
+    cells: impl Iterator<Item = Cow<'t, str>>,
+) {
+    match format {
+        ClipFormat::Tsv => {
+            for (i, cell) in cells.enumerate() {
+                if i > 0 {
+                    out.push('\t');
+                }
+                out.extend(cell.chars().map(|c| {
+                    if matches!(c, '\t' | '\n' | '\r') {
+                        ' '
+                    } else {
+                        c
+                    }
+                }));
+            }
+            out.push_str("\r\n");
+        }
+        ClipFormat::Csv => {
+            for (i, cell) in cells.enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write_csv_field(out, &cell);
+            }
+            out.push_str("\r\n");
+        }
+        ClipFormat::Html => {
+            let tag = if header { "th" } else { "td" };
+            out.push_str("<tr>");
+            for cell in cells {
+                let _ = write!(out, "<{}>{}</{}>", tag, escape_html(&cell), tag);
+            }
+            out.push_str("</tr>");
+        }
+    }
+}
+
+/// Quotes a CSV field when it holds a comma, quote or line break, doubling quotes.
+pub fn write_csv_field(out: &mut impl Write, field: &str) -> std::fmt::Result {
+    if field.contains([',', '"', '\n', '\r']) {
+        write!(out, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        out.write_str(field)
+    }
+}
+
+pub fn escape_html(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>', '"']) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
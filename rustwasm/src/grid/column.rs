@@ -1,20 +1,57 @@
 #![allow(dead_code)]
+use crate::grid::datefmt::*;
+use crate::grid::decimal::*;
+use crate::grid::locale::*;
+use crate::grid::measure::*;
+use crate::grid::numfmt::*;
+use crate::grid::price::*;
 use crate::grid::renderer::*;
-use chrono::prelude::*;
-use chrono::Local;
 use serde::{Deserialize, Serialize};
 use serde_repr::*;
 
-#[derive(Debug, PartialEq, Serialize_repr, Deserialize_repr, Copy, Clone)]
+#[derive(Debug, PartialEq, Serialize_repr, Deserialize_repr, Copy, Clone, Default)]
 #[repr(u32)]
 pub enum ColumnType {
     None = 0,
     String,
+    #[default]
     Number,
     Date,
     DateTime,
     Timestamp,
     Sparkline,
+    Decimal,
+    Symbol,
+    VarString, // u32 offset + u32 length into the DataSource strings buffer
+    Duration,  // f64 in `epoch_unit` units, e.g. a latency
+}
+
+/// How "no value" is encoded in the row buffer.
+#[derive(Debug, PartialEq, Serialize_repr, Deserialize_repr, Copy, Clone, Default)]
+#[repr(u32)]
+pub enum NullMode {
+    #[default]
+    None = 0,
+    Sentinel, // NaN for numbers/dates, i64::MIN for decimals
+    Bitmap,   // validity bitmap at the start of each row, one bit per column
+}
+
+pub const DECIMAL_NULL: i64 = i64::MIN;
+
+/// Grid-wide settings cell formatting depends on.
+#[derive(Copy, Clone)]
+pub struct FormatContext<'a> {
+    pub locale: &'a Locale,
+    pub clock: &'a dyn Clock, // "now" for relative times and the local zone
+}
+
+impl Default for FormatContext<'_> {
+    fn default() -> Self {
+        FormatContext {
+            locale: &EN_US,
+            clock: &SystemClock,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -23,6 +60,8 @@ pub struct Column {
     pub name: String,
     #[serde(default)]
     pub description: String,
+    #[serde(default)]
+    pub group: Vec<String>, // header groups, outermost first, e.g. ["Book", "Bid"]
     pub col_type: ColumnType,
     #[serde(default)]
     pub data_offset: usize,
@@ -31,50 +70,107 @@ pub struct Column {
     #[serde(default)]
     pub precision: usize,
     #[serde(default)]
+    pub scale: u32, // Decimal: number of implied fraction digits in the stored i64
+    #[serde(default)]
     pub hidden: bool,
     #[serde(default)]
+    pub width: f64, // pixels, 0 - share of the space left by the other columns
+    #[serde(default)]
     pub align: String, // "left", "center", "right"
     #[serde(default)]
+    pub overflow: Overflow, // text wider than the cell, numbers always show ###
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
     pub highlight: bool,
     #[serde(default)]
     pub suppress_zero: bool, // switch to format later
     #[serde(default)]
     pub format: String,
-}
-
-impl Default for ColumnType {
-    fn default() -> Self {
-        ColumnType::Number
-    }
+    #[serde(default)]
+    pub prefix: String, // e.g. currency symbol
+    #[serde(default)]
+    pub suffix: String,
+    #[serde(default)]
+    pub price: Option<PriceFormat>, // tick/fraction price display, overrides `format`
+    #[serde(default)]
+    pub epoch_unit: EpochUnit, // dates and durations: unit of the stored value
+    #[serde(default)]
+    pub timezone: String, // dates: "" local, "UTC", "+05:30" or "America/New_York"
+    #[serde(skip)]
+    pub number_format: Option<NumberFormat>, // parsed `format`, set by normalize_schema
+    #[serde(skip)]
+    pub zone: Option<Zone>, // parsed `timezone`, set by normalize_schema
+    #[serde(skip)]
+    pub null_mode: NullMode, // copied from the schema by normalize_schema
+    #[serde(skip)]
+    pub validity_index: usize, // bit position in the row validity bitmap
 }
 
 impl Column {
     pub fn precision(&self) -> usize {
         match self.col_type {
-            ColumnType::Number => self.precision,
+            ColumnType::Number | ColumnType::Decimal | ColumnType::Duration => self.precision,
             _ => 0,
         }
     }
 
     pub fn format_value(&self, value: Option<f64>) -> Option<String> {
-        if value.is_some() {
-            let v = value.unwrap();
+        self.format_value_with(value, &FormatContext::default())
+    }
+
+    /// Same as `format_value` with the grid locale and clock.
+    pub fn format_value_with(&self, value: Option<f64>, fc: &FormatContext) -> Option<String> {
+        if let Some(v) = value {
             let formatted = match self.col_type {
-                ColumnType::DateTime | ColumnType::Timestamp | ColumnType::Date => Local
-                    .timestamp(v as i64 / 1000, 0)
-                    .format(if self.format.is_empty() {
+                ColumnType::DateTime | ColumnType::Timestamp | ColumnType::Date
+                    if self.format == RELATIVE_FORMAT =>
+                {
+                    format_relative(v, self.epoch_unit, fc.clock.now_ms())
+                }
+                ColumnType::DateTime | ColumnType::Timestamp | ColumnType::Date => format_epoch(
+                    v,
+                    self.epoch_unit,
+                    self.zone(),
+                    if self.format.is_empty() {
                         "%r"
                     } else {
                         &self.format
-                    })
-                    .to_string(),
+                    },
+                    fc.clock,
+                    fc.locale,
+                ),
                 ColumnType::Number => {
                     if v == 0.0 && self.suppress_zero {
                         String::from("")
+                    } else if let Some(price) = &self.price {
+                        [
+                            self.prefix.as_str(),
+                            &fc.locale
+                                .localize_decimal(price.format(v, self.precision())),
+                            &self.suffix,
+                        ]
+                        .concat()
                     } else {
-                        format_args!("{:.*}", self.precision(), v).to_string()
+                        self.with_number_format(|nf| {
+                            nf.format_f64(
+                                v,
+                                self.precision(),
+                                &self.prefix,
+                                &self.suffix,
+                                fc.locale,
+                            )
+                        })
                     }
                 }
+                ColumnType::Duration => fc.locale.localize_decimal(format_duration(
+                    v,
+                    self.epoch_unit,
+                    DurationStyle::parse(&self.format).unwrap_or_default(),
+                    self.precision(),
+                )),
                 ColumnType::Sparkline => String::from(""),
                 _ => v.to_string(),
             };
@@ -84,4 +180,97 @@ impl Column {
             None
         }
     }
+
+    pub fn format_decimal(&self, value: Option<Decimal>) -> Option<String> {
+        self.format_decimal_with(value, &FormatContext::default())
+    }
+
+    pub fn format_decimal_with(
+        &self,
+        value: Option<Decimal>,
+        fc: &FormatContext,
+    ) -> Option<String> {
+        value.map(|v| {
+            if v.is_zero() && self.suppress_zero {
+                String::from("")
+            } else if let Some(price) = &self.price {
+                [
+                    self.prefix.as_str(),
+                    &fc.locale
+                        .localize_decimal(price.format_decimal(v, self.precision())),
+                    &self.suffix,
+                ]
+                .concat()
+            } else {
+                self.with_number_format(|nf| {
+                    nf.format_decimal(v, self.precision(), &self.prefix, &self.suffix, fc.locale)
+                })
+            }
+        })
+    }
+
+    /// Price split for emphasized rendering, `None` unless the column asks for it.
+    pub fn format_price_parts(&self, value: Option<f64>) -> Option<PriceParts> {
+        self.format_price_parts_with(value, &FormatContext::default())
+    }
+
+    pub fn format_price_parts_with(
+        &self,
+        value: Option<f64>,
+        fc: &FormatContext,
+    ) -> Option<PriceParts> {
+        let price = self.price.as_ref().filter(|p| p.big_figure_digits > 0)?;
+        let v = value?;
+        if v == 0.0 && self.suppress_zero {
+            return None;
+        }
+        Some(self.localize_price(price.format_parts(v, self.precision()), fc))
+    }
+
+    /// Same as `format_price_parts_with`, exact for Decimal columns.
+    pub fn format_decimal_price_parts_with(
+        &self,
+        value: Option<Decimal>,
+        fc: &FormatContext,
+    ) -> Option<PriceParts> {
+        let price = self.price.as_ref().filter(|p| p.big_figure_digits > 0)?;
+        let v = value?;
+        if v.is_zero() && self.suppress_zero {
+            return None;
+        }
+        Some(self.localize_price(price.format_decimal_parts(v, self.precision()), fc))
+    }
+
+    fn localize_price(&self, parts: PriceParts, fc: &FormatContext) -> PriceParts {
+        PriceParts {
+            lead: self.prefix.clone() + &fc.locale.localize_decimal(parts.lead),
+            big_figure: fc.locale.localize_decimal(parts.big_figure),
+            tail: fc.locale.localize_decimal(parts.tail) + &self.suffix,
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self.col_type, ColumnType::Number | ColumnType::Decimal)
+    }
+
+    /// Cells showing a number: numeric font, ### when they don't fit.
+    pub fn shows_number(&self) -> bool {
+        self.is_numeric() || self.col_type == ColumnType::Duration
+    }
+
+    fn zone(&self) -> Zone {
+        match self.zone {
+            Some(zone) => zone,
+            // not normalized - parse on the fly
+            None => Zone::parse(&self.timezone).unwrap_or_default(),
+        }
+    }
+
+    fn with_number_format<T>(&self, f: impl FnOnce(&NumberFormat) -> T) -> T {
+        match &self.number_format {
+            Some(nf) => f(nf),
+            // not normalized - parse on the fly
+            None => f(&NumberFormat::parse(&self.format).unwrap_or_default()),
+        }
+    }
 }
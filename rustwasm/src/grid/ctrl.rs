@@ -1,7 +1,26 @@
+use super::autofit::*;
+use super::clipboard::*;
+use super::column::*;
+use super::datefmt::*;
 use super::ds::*;
+use super::events::*;
+use super::export::*;
+use super::layout::*;
+use super::locale::*;
+use super::measure::*;
+use super::pointer::*;
 use super::renderer::*;
 use super::schema::*;
+use super::selection::*;
+use super::state::*;
+use super::symbols::*;
+use super::theme::*;
+use super::tooltip::*;
+use super::validate::*;
+use crate::error::*;
 use crate::utils::*;
+use chrono::Utc;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
@@ -21,71 +40,648 @@ pub struct Grid {
     id: String,
     schema: Schema,
     sparks: Sparks,
+    symbols: SymbolTable,
     top_index: usize,
     row_height: usize,
+    clock: Option<FixedClock>, // None - system clock
+    locale: Locale,
+    theme: Theme,
+    measure_cache: RefCell<MeasureCache>,
+    auto_fit: Option<FitMode>,    // refit on schema change
+    fit_request: Option<FitMode>, // fit on the next render
+    query: ViewQuery,             // kept for the host, see `get_state`
+    header: HeaderLayout,         // of the last render, for pointer events
+    pointer: PointerState,
+    window: DataWindow, // rows of the last render, for tooltips and clicks
+    row_keys: Vec<Option<RowKey>>, // of `window`, for clicks
+    selection: Selection,
+    resolved: ResolvedSelection, // of the last render, finds rows scrolled out of view
+    selecting: bool,             // pointer held down on a cell
+    nav: Vec<(Nav, bool)>,       // keys since the last render, with shift
+    callbacks: Callbacks,
+}
+
+fn clock(clock: &Option<FixedClock>) -> &dyn Clock {
+    match clock {
+        Some(clock) => clock,
+        None => &SystemClock,
+    }
+}
+
+/// Host buffer checked against the schema, see `validate_layout`.
+fn data_source<'a>(
+    schema: &Schema,
+    sparks: &'a Sparks,
+    symbols: &'a SymbolTable,
+    data: &'a [SZ],
+    strings: &'a [SZ],
+    data_width: usize,
+) -> GridResult<DataSource<'a>> {
+    let data = validate_layout(schema, data, data_width)?;
+    Ok(DataSource::new(data, data_width, Some(sparks))
+        .with_symbols(symbols)
+        .with_strings(strings))
 }
 
 #[wasm_bindgen]
 impl Grid {
-    pub fn new(id: String, schema: &JsValue) -> Grid {
+    pub fn new(id: String, schema: &JsValue) -> Result<Grid, JsValue> {
         set_panic_hook();
-        Grid {
+        Ok(Grid {
             id,
-            schema: Grid::set_schema(schema),
+            schema: Grid::parse_schema(schema)?,
             ..Default::default()
-        }
+        })
     }
 
     pub fn render(
-        &self,
+        &mut self,
         data: &[SZ],
         data_width: usize,
         top: u32,
         left: u32,
         width: u32,
         height: u32,
-    ) {
-        GridRenderer::new(
-            &ctx(&self.id),
+    ) -> Result<(), JsValue> {
+        self.render_with_strings(data, &[], data_width, top, left, width, height)
+    }
+
+    /// Same as `render`, with the side buffer holding the bytes of `VarString` columns.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_with_strings(
+        &mut self,
+        data: &[SZ],
+        strings: &[SZ],
+        data_width: usize,
+        top: u32,
+        left: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), JsValue> {
+        let ds = data_source(
             &self.schema,
-            left,
-            top,
-            width,
-            height,
-            self.row_height,
+            &self.sparks,
+            &self.symbols,
+            data,
+            strings,
+            data_width,
+        )?;
+        let ctx = ctx(&self.id)?;
+        let format = FormatContext {
+            locale: &self.locale,
+            clock: clock(&self.clock),
+        };
+        let (selection, top_index) = (self.selection.clone(), self.top_index);
+        let fitted = self.fit_request.is_some();
+        if let Some(mode) = self.fit_request.take() {
+            let mut gr = GridRenderer::new(
+                &ctx,
+                &self.schema,
+                left,
+                top,
+                width,
+                height,
+                self.row_height,
+            )
+            .with_theme(&self.theme)
+            .with_measure_cache(&self.measure_cache)
+            .with_format(format);
+            gr.set_top_index(self.top_index);
+            let widths = gr.fit_col_widths(&ds, mode);
+            for (col, width) in self.schema.cols.iter_mut().zip(widths) {
+                if let Some(width) = width {
+                    col.width = width;
+                }
+            }
+        }
+        let schema = &self.schema;
+        let mut resolved = ResolvedSelection::default();
+        let gr = GridRenderer::new(&ctx, schema, left, top, width, height, self.row_height)
+            .with_theme(&self.theme)
+            .with_measure_cache(&self.measure_cache)
+            .with_format(format);
+
+        // keyboard moves wait for the data, the cursor is found by its row key
+        let key_col = schema.get_key_col();
+        let row_key = |row: usize| ds.row_key(row, key_col);
+        let col_index = |id: &str| schema.get_visible_cols().position(|c| c.id == id);
+        let col_id = |i: usize| schema.get_visible_cols().nth(i).map(|c| c.id.to_string());
+        let visible_rows = gr.visible_row_count();
+        let visible = |top: usize| top.min(ds.row_count)..ds.row_count.min(top + visible_rows);
+        if !self.selection.is_empty() || !self.nav.is_empty() {
+            let last = std::mem::take(&mut self.resolved);
+            resolved = self
+                .selection
+                .resolve(visible(self.top_index), &last, &row_key, &col_index);
+            for (nav, shift) in self.nav.drain(..) {
+                if resolved.cursor.is_none() && self.selection.cursor.is_some() {
+                    // the cursor is out of view and moved, look for it everywhere
+                    resolved = self
+                        .selection
+                        .resolve(0..ds.row_count, &last, &row_key, &col_index);
+                }
+                let moved = self.selection.navigate(
+                    nav,
+                    shift,
+                    &resolved,
+                    ds.row_count,
+                    schema.visible_col_count,
+                    gr.page_size(),
+                    &row_key,
+                    &col_id,
+                );
+                if let Some(row) = moved {
+                    self.top_index = scroll_to(row, self.top_index, gr.page_size());
+                }
+                resolved = self.selection.resolve(
+                    visible(self.top_index),
+                    &resolved,
+                    &row_key,
+                    &col_index,
+                );
+            }
+        }
+
+        let mut gr = gr.with_selection(&resolved);
+        gr.render(&ds, self.top_index);
+        let header = gr.header_layout();
+        let rows = visible(self.top_index);
+        let row_keys = rows.clone().map(row_key).collect();
+        let window = DataWindow::capture(&schema.cols, &ds, rows);
+        if let Some(guide) = self.pointer.guide(&header) {
+            gr.render_guide(guide);
+        }
+        self.header = header;
+        self.window = window;
+        self.row_keys = row_keys;
+        self.resolved = resolved;
+
+        self.emit_selection(&selection)?;
+        if self.top_index != top_index {
+            self.callbacks.emit(&GridEvent::ScrollChange {
+                top_index: self.top_index,
+            })?;
+        }
+        if fitted {
+            self.emit_layout()?;
+        }
+        Ok(())
+    }
+
+    /// Calls `callback` with the payload of every `kind` event, `undefined` removes it.
+    /// Cell clicks get `{ row, col, value }` with the row key and the raw value,
+    /// selection changes the `get_selection` object, sort changes `{ sort }`,
+    /// scroll changes `{ top_index }` and column layout changes `{ columns }`
+    /// as in `get_state`. Only changes made inside the canvas are reported.
+    /// Callbacks run as microtasks after the call that raised the event returns,
+    /// so they are free to call back into the grid.
+    pub fn on(&mut self, kind: GridEventKind, callback: Option<js_sys::Function>) {
+        self.callbacks.set(kind, callback);
+    }
+
+    /// Reports a double click on a data cell, true when a cell was hit.
+    pub fn double_click(&self, x: f64, y: f64) -> Result<bool, JsValue> {
+        match self.cell_event(x, y) {
+            Some(cell) => {
+                self.callbacks.emit(&GridEvent::CellDoubleClick(cell))?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn cell_event(&self, x: f64, y: f64) -> Option<CellEvent> {
+        let (row, col) = self.header.data_cell_at(x, y)?;
+        let id = self.header.ids.get(col)?;
+        let column = self.schema.get_col_by_id(id)?;
+        Some(CellEvent {
+            row: self.row_keys.get(row)?.clone()?,
+            col: id.to_string(),
+            value: cell_value(
+                &self
+                    .window
+                    .data_source(Some(&self.sparks), Some(&self.symbols)),
+                row,
+                column,
+            ),
+            side: None,
+        })
+    }
+
+    fn emit_selection(&self, before: &Selection) -> Result<(), JsValue> {
+        if self.selection == *before {
+            return Ok(());
+        }
+        self.callbacks
+            .emit(&GridEvent::SelectionChange(self.selection.clone()))
+    }
+
+    fn emit_layout(&self) -> Result<(), JsValue> {
+        if !self.callbacks.has(GridEventKind::ColumnLayoutChange) {
+            return Ok(());
+        }
+        self.callbacks.emit(&GridEvent::ColumnLayoutChange {
+            columns: ViewState::capture(&self.schema).columns,
+        })
+    }
+
+    /// Selected cells of `data` as TSV, CSV or an HTML table, displayed text or raw values,
+    /// optionally with the column names first. Takes the buffers of the last `render`.
+    pub fn copy_selection(
+        &self,
+        data: &[SZ],
+        strings: &[SZ],
+        data_width: usize,
+        format: ClipFormat,
+        raw: bool,
+        header: bool,
+    ) -> Result<String, JsValue> {
+        let ds = self.data_source(data, strings, data_width)?;
+        let schema = &self.schema;
+        let key_col = schema.get_key_col();
+        let resolved = self.selection.resolve(
+            0..ds.row_count,
+            &self.resolved,
+            &|row| ds.row_key(row, key_col),
+            &|id| schema.get_visible_cols().position(|c| c.id == id),
+        );
+        Ok(copy_cells(
+            schema,
+            &ds,
+            &resolved,
+            &self.format_context(),
+            ClipOptions {
+                format,
+                raw,
+                header,
+            },
+        ))
+    }
+
+    /// The visible columns of rows `start..end` of `data` as CSV or JSON Lines, UTF-8
+    /// bytes for one `Blob` part. Consecutive ranges export a large buffer in chunks,
+    /// the CSV header comes with the range starting at 0. Empty past the last row.
+    #[allow(clippy::too_many_arguments)]
+    pub fn export_rows(
+        &self,
+        data: &[SZ],
+        strings: &[SZ],
+        data_width: usize,
+        format: ExportFormat,
+        raw: bool,
+        header: bool,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<u8>, JsValue> {
+        let ds = self.data_source(data, strings, data_width)?;
+        let mut out = Vec::new();
+        export_rows(
+            &mut out,
+            &self.schema,
+            &ds,
+            start..end,
+            &self.format_context(),
+            ExportOptions {
+                format,
+                raw,
+                header,
+            },
+        )
+        .map_err(|e| GridError::new(ErrorKind::InvalidData, e.to_string()))?;
+        Ok(out)
+    }
+
+    fn data_source<'a>(
+        &'a self,
+        data: &'a [SZ],
+        strings: &'a [SZ],
+        data_width: usize,
+    ) -> GridResult<DataSource<'a>> {
+        data_source(
+            &self.schema,
+            &self.sparks,
+            &self.symbols,
+            data,
+            strings,
+            data_width,
         )
-        .render(
-            &DataSource::new(data, data_width, Some(&self.sparks)),
-            self.top_index,
+    }
+
+    fn format_context(&self) -> FormatContext<'_> {
+        FormatContext {
+            locale: &self.locale,
+            clock: clock(&self.clock),
+        }
+    }
+
+    /// Cell at canvas coordinates as of the last render.
+    fn cell_at(&self, x: f64, y: f64) -> Option<CellRef> {
+        let (row, col) = self.header.data_cell_at(x, y)?;
+        Some(CellRef {
+            row: self.row_keys.get(row)?.clone()?,
+            col: self.header.ids.get(col)?.to_string(),
+        })
+    }
+
+    /// `{ text, x, y, width, height }` for the header or cell at canvas coordinates,
+    /// `undefined` where there is nothing to show. Reflects the last render.
+    pub fn tooltip_at(&self, x: f64, y: f64) -> Result<JsValue, JsValue> {
+        let ds = self
+            .window
+            .data_source(Some(&self.sparks), Some(&self.symbols));
+        let format = self.format_context();
+        let text = |row: usize, col: &Column| {
+            (row < ds.row_count)
+                .then(|| tooltip_text(&ds, row, col, &format, &self.schema.null_text))
+        };
+        match tooltip_at(&self.header, &self.schema, text, x, y) {
+            Some(tooltip) => Ok(serde_wasm_bindgen::to_value(&tooltip).map_err(GridError::from)?),
+            None => Ok(JsValue::UNDEFINED),
+        }
+    }
+
+    /// Pointer pressed at canvas coordinates: starts a header drag, or selects a cell,
+    /// shift extending the range and ctrl adding the row. True when the host should
+    /// capture the pointer.
+    pub fn pointer_down(
+        &mut self,
+        x: f64,
+        y: f64,
+        shift: bool,
+        ctrl: bool,
+    ) -> Result<bool, JsValue> {
+        if self.pointer.down(&self.header, x, y) {
+            return Ok(true);
+        }
+        match self.cell_at(x, y) {
+            Some(cell) => {
+                let before = self.selection.clone();
+                self.selection.click(cell, shift, ctrl);
+                self.selecting = true;
+                if let Some(event) = self.cell_event(x, y) {
+                    self.callbacks.emit(&GridEvent::CellClick(event))?;
+                }
+                self.emit_selection(&before)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// True when the grid needs a render to show the drag.
+    pub fn pointer_move(&mut self, x: f64, y: f64) -> Result<bool, JsValue> {
+        if self.selecting {
+            if let Some(cell) = self.cell_at(x, y) {
+                let before = self.selection.clone();
+                self.selection.click(cell, true, false);
+                self.emit_selection(&before)?;
+            }
+            return Ok(true);
+        }
+        let action = self.pointer.move_to(&self.header, x);
+        self.apply_pointer(action)
+    }
+
+    /// Finishes a resize, moves the dragged column or toggles the sort of a clicked
+    /// header, true when the grid needs a render. Sorting the data is up to the host.
+    pub fn pointer_up(&mut self, x: f64, _y: f64) -> Result<bool, JsValue> {
+        if std::mem::take(&mut self.selecting) {
+            return Ok(true);
+        }
+        let action = self.pointer.up(&self.header, x);
+        let layout = matches!(
+            action,
+            PointerAction::Resize { .. } | PointerAction::Move { .. }
         );
+        let redraw = self.apply_pointer(action)?;
+        if layout {
+            self.emit_layout()?;
+        }
+        Ok(redraw)
+    }
+
+    /// Arrow, page, Home and End keys (`KeyboardEvent.key`) move the cursor on the next
+    /// render, shift extends the range, Escape clears it. True when the key was used.
+    pub fn key_down(&mut self, key: &str, shift: bool, ctrl: bool) -> Result<bool, JsValue> {
+        if key == "Escape" {
+            let before = std::mem::take(&mut self.selection);
+            self.emit_selection(&before)?;
+            return Ok(true);
+        }
+        match Nav::parse(key, ctrl) {
+            Some(nav) => {
+                self.nav.push((nav, shift));
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// `{ cursor, anchor, rows }` with cells as `{ row: <row key>, col: <column id> }`.
+    pub fn get_selection(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.selection).map_err(GridError::from)?)
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+        self.nav.clear();
+    }
+
+    /// Drops a drag in progress, e.g. on pointercancel, keeping widths resized so far.
+    pub fn pointer_cancel(&mut self) -> bool {
+        self.pointer.cancel()
+    }
+
+    /// CSS cursor for the canvas at these coordinates.
+    pub fn get_cursor(&self, x: f64, y: f64) -> String {
+        self.pointer.cursor(&self.header, x, y).to_string()
+    }
+
+    fn apply_pointer(&mut self, action: PointerAction) -> Result<bool, JsValue> {
+        match action {
+            PointerAction::None => Ok(false),
+            PointerAction::Redraw => Ok(true),
+            PointerAction::Resize { id, width } => {
+                self.schema.set_col_width(&id, width)?;
+                Ok(true)
+            }
+            PointerAction::Move { id, slot } => {
+                if let Some(to) = drop_position(&self.schema, &id, slot) {
+                    self.schema.move_col(&id, to)?;
+                }
+                Ok(true)
+            }
+            PointerAction::Click { id } => {
+                self.query.toggle_sort(&id);
+                self.callbacks.emit(&GridEvent::SortChange {
+                    sort: self.query.sort.clone(),
+                })?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Replaces the schema, columns are refitted on the next render when `set_auto_fit` is on.
+    pub fn set_schema(&mut self, obj: &JsValue) -> Result<(), JsValue> {
+        self.schema = Grid::parse_schema(obj)?;
+        self.fit_request = self.auto_fit;
+        Ok(())
+    }
+
+    pub fn set_col_hidden(&mut self, id: &str, hidden: bool) -> Result<(), JsValue> {
+        self.schema.set_col_hidden(id, hidden)?;
+        self.fit_request = self.fit_request.or(self.auto_fit);
+        Ok(())
+    }
+
+    /// Moves a column to display position `to`, the buffer layout is unchanged.
+    pub fn move_col(&mut self, id: &str, to: usize) -> Result<(), JsValue> {
+        Ok(self.schema.move_col(id, to)?)
+    }
+
+    /// 0 makes the column share the remaining space again.
+    pub fn set_col_width(&mut self, id: &str, width: f64) -> Result<(), JsValue> {
+        Ok(self.schema.set_col_width(id, width)?)
+    }
+
+    pub fn set_col_format(&mut self, id: &str, format: &str) -> Result<(), JsValue> {
+        self.schema.set_col_format(id, format)?;
+        self.fit_request = self.fit_request.or(self.auto_fit);
+        Ok(())
     }
 
-    fn set_schema(obj: &JsValue) -> Schema {
-        console_error_panic_hook::set_once();
-        let mut schema = obj.into_serde::<Schema>().unwrap_or_default();
-        normalize_schema(&mut schema);
-        schema
+    /// Column ids in display order, hidden columns included.
+    pub fn get_col_order(&self) -> Result<JsValue, JsValue> {
+        let ids: Vec<&str> = self
+            .schema
+            .get_display_cols()
+            .map(|col| col.id.as_str())
+            .collect();
+        Ok(serde_wasm_bindgen::to_value(&ids).map_err(GridError::from)?)
     }
 
-    pub fn set_sparks(&mut self, obj: &JsValue) {
-        console_error_panic_hook::set_once();
-        let temp = obj
-            .into_serde::<HashMap<String, Vec<f64>>>()
-            .unwrap_or_default();
+    fn parse_schema(obj: &JsValue) -> GridResult<Schema> {
+        let mut schema: Schema = from_js(obj)?;
+        normalize_schema(&mut schema)?;
+        Ok(schema)
+    }
+
+    /// Sizes columns to their header and cell text on the next render, which has the data.
+    /// The widths are kept in the schema until the next fit or schema change.
+    pub fn auto_fit_columns(&mut self, mode: FitMode) {
+        self.fit_request = Some(mode);
+    }
+
+    /// Refits columns after every `set_schema`, `None` keeps the schema widths.
+    pub fn set_auto_fit(&mut self, mode: Option<FitMode>) {
+        self.auto_fit = mode;
+        self.fit_request = mode;
+    }
+
+    /// Width of each schema column, 0 for columns sharing the remaining space.
+    pub fn get_col_widths(&self) -> Vec<f64> {
+        self.schema.cols.iter().map(|col| col.width).collect()
+    }
+
+    /// Versioned JSON with the column layout, sort, filters, group-by, scroll and row height.
+    pub fn get_state(&self) -> String {
+        ViewState {
+            query: self.query.clone(),
+            top_index: self.top_index,
+            row_height: self.row_height,
+            ..ViewState::capture(&self.schema)
+        }
+        .to_json()
+    }
+
+    /// Restores `get_state` output; columns added or removed since are tolerated.
+    pub fn set_state(&mut self, json: &str) -> Result<(), JsValue> {
+        let state = ViewState::from_json(json)?.restore(&mut self.schema)?;
+        self.query = state.query;
+        self.top_index = state.top_index;
+        self.row_height = state.row_height;
+        self.fit_request = None; // keep the restored widths
+        Ok(())
+    }
+
+    pub fn set_sparks(&mut self, obj: &JsValue) -> Result<(), JsValue> {
+        let temp: HashMap<String, Vec<f64>> = from_js(obj)?;
         self.sparks = Sparks::new();
         for (key, val) in temp.iter() {
             self.sparks.insert(hash_code(key), val.to_vec());
         }
+        Ok(())
+    }
+
+    /// Replaces the string table used by `Symbol` columns.
+    pub fn set_symbols(&mut self, obj: &JsValue) -> Result<(), JsValue> {
+        let strings: Vec<String> = from_js(obj)?;
+        self.symbols = SymbolTable::from_strings(strings);
+        Ok(())
+    }
+
+    /// Appends to the string table; indices already in the buffers stay valid.
+    pub fn add_symbols(&mut self, obj: &JsValue) -> Result<usize, JsValue> {
+        let strings: Vec<String> = from_js(obj)?;
+        self.symbols.extend(strings);
+        Ok(self.symbols.len())
+    }
+
+    pub fn get_symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Problems found in the current schema, see `validate_schema`.
+    pub fn validate_schema(&self) -> Result<JsValue, JsValue> {
+        problems_to_js(&validate_schema(
+            &self.schema,
+            &ValidateOptions {
+                has_sparks: Some(self.has_sparks()),
+                ..Default::default()
+            },
+        ))
     }
 
     pub fn has_sparks(&self) -> bool {
-        self.sparks.len() > 0
+        !self.sparks.is_empty()
     }
 
     pub fn set_top_index(&mut self, top_index: usize) {
         self.top_index = top_index;
     }
 
+    /// Freezes "now" for relative times, e.g. when replaying, `None` goes back to the system clock.
+    /// Relative times are recomputed on every `render`.
+    pub fn set_clock(&mut self, now_ms: Option<f64>) {
+        self.clock = now_ms.map(|now_ms| FixedClock {
+            now_ms,
+            offset: SystemClock.local_offset(&Utc::now().naive_utc()),
+        });
+    }
+
+    /// Sets separators and date names, e.g. "de-DE" renders 1.234,56.
+    pub fn set_locale(&mut self, tag: &str) -> Result<(), JsValue> {
+        self.locale =
+            Locale::find(tag).map_err(|e| GridError::new(ErrorKind::InvalidArgument, e))?;
+        Ok(())
+    }
+
+    pub fn get_locale(&self) -> String {
+        self.locale.tag.to_string()
+    }
+
+    /// Takes "dark", "light" or a theme object, missing colors keep the dark preset.
+    pub fn set_theme(&mut self, obj: &JsValue) -> Result<(), JsValue> {
+        self.theme = theme_from_js(obj)?;
+        self.measure_cache.borrow_mut().clear();
+        Ok(())
+    }
+
+    pub fn get_theme(&self) -> Result<JsValue, JsValue> {
+        Ok(serde_wasm_bindgen::to_value(&self.theme).map_err(GridError::from)?)
+    }
+
+    /// 0 derives the row height from the theme fonts.
     pub fn set_row_height(&mut self, row_height: usize) {
         self.row_height = row_height;
     }
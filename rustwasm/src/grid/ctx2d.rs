@@ -16,6 +16,9 @@ pub fn _fill_text(ctx: &CanvasRenderingContext2d, v: &str, x: f64, y: f64) {
     ctx.fill_text(v, x, y).unwrap();
 }
 
+pub const PADDING_H: f64 = 10.0;
+
+#[allow(clippy::too_many_arguments)]
 pub fn fill_text_aligned(
     ctx: &CanvasRenderingContext2d,
     v: &str,
@@ -24,15 +27,14 @@ pub fn fill_text_aligned(
     width: f64,
     height: f64,
     text_align: &str,
-    highlight: bool,
+    highlight: Option<&str>, // text color of highlighted cells
 ) {
-    const PADDING_H: f64 = 10.0;
     let dy = y + height / 2.0;
     set_text_align(ctx, text_align);
 
-    if highlight {
+    if let Some(color) = highlight {
         ctx.save();
-        set_fill_style(ctx, "white");
+        set_fill_style(ctx, color);
     }
 
     match text_align {
@@ -41,11 +43,52 @@ pub fn fill_text_aligned(
         _ => ctx.fill_text(v, x + PADDING_H, dy).unwrap(),
     };
 
-    if highlight {
+    if highlight.is_some() {
         ctx.restore();
     }
 }
 
+/// Draws consecutive text runs as one aligned string, each run with its own font
+/// (`None` keeps the current one). Used for emphasized price digits.
+#[allow(clippy::too_many_arguments)]
+pub fn fill_text_runs(
+    ctx: &CanvasRenderingContext2d,
+    runs: &[(&str, Option<&str>)],
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    text_align: &str,
+    highlight: Option<&str>,
+) {
+    ctx.save();
+    let base_font = ctx.font();
+    let widths: Vec<f64> = runs
+        .iter()
+        .map(|(text, font)| {
+            ctx.set_font(font.unwrap_or(&base_font));
+            measure_text(ctx, text).0
+        })
+        .collect();
+    let total: f64 = widths.iter().sum();
+    let mut left = match text_align {
+        "right" => x + width - PADDING_H - total,
+        "center" => x + (width - total) / 2.0,
+        _ => x + PADDING_H,
+    };
+
+    set_text_align(ctx, "left");
+    if let Some(color) = highlight {
+        set_fill_style(ctx, color);
+    }
+    for ((text, font), w) in runs.iter().zip(widths) {
+        ctx.set_font(font.unwrap_or(&base_font));
+        ctx.fill_text(text, left, y + height / 2.0).unwrap();
+        left += w;
+    }
+    ctx.restore();
+}
+
 pub fn horizontal_line(ctx: &CanvasRenderingContext2d, left: f64, right: f64, y: f64) {
     ctx.move_to(left, y);
     ctx.line_to(right, y);
@@ -57,11 +100,11 @@ pub fn vertical_line(ctx: &CanvasRenderingContext2d, top: f64, bottom: f64, x: f
 }
 
 pub fn _set_stroke(ctx: &CanvasRenderingContext2d, color: &str) {
-    ctx.set_stroke_style(&color.into());
+    ctx.set_stroke_style_str(color);
 }
 
 pub fn set_fill_style(ctx: &CanvasRenderingContext2d, color: &str) {
-    ctx.set_fill_style(&color.into());
+    ctx.set_fill_style_str(color);
 }
 
 pub fn fill_rect(
@@ -72,7 +115,7 @@ pub fn fill_rect(
     height: f64,
     color: &str,
 ) {
-    set_fill_style(ctx, &color);
+    set_fill_style(ctx, color);
     //ctx.clear_rect(left, top, width, height);
     ctx.fill_rect(left, top, width, height);
 }
@@ -89,24 +132,39 @@ pub fn clip_end(ctx: &CanvasRenderingContext2d) {
 }
 
 pub fn set_text_align(ctx: &CanvasRenderingContext2d, align: &str) {
-    ctx.set_text_align(&align);
+    ctx.set_text_align(align);
 }
 
 pub fn set_text_baseline(ctx: &CanvasRenderingContext2d, align: &str) {
-    ctx.set_text_baseline(&align);
+    ctx.set_text_baseline(align);
 }
 
-pub fn _set_font(ctx: &CanvasRenderingContext2d, font: &str) {
+pub fn set_font(ctx: &CanvasRenderingContext2d, font: &str) {
     ctx.set_font(font);
 }
 
-pub fn _measure_text(ctx: &CanvasRenderingContext2d, text: &str) -> (f64, f64) {
-    let metrics: ExtendedTextMetrics = ctx.measure_text(&text).unwrap().unchecked_into();
+/// Width and inked height of the text in the current font.
+pub fn measure_text(ctx: &CanvasRenderingContext2d, text: &str) -> (f64, f64) {
+    let metrics: ExtendedTextMetrics = ctx.measure_text(text).unwrap().unchecked_into();
     let width = metrics.width();
-    let height = metrics.actual_bounding_box_ascent() - metrics.actual_bounding_box_descent();
+    // descent is positive below the baseline
+    let height = metrics.actual_bounding_box_ascent() + metrics.actual_bounding_box_descent();
     (width, height)
 }
 
+/// Line height of a font: the font bounding box where supported, "Mg" otherwise.
+pub fn font_height(ctx: &CanvasRenderingContext2d, font: &str) -> f64 {
+    ctx.save();
+    set_font(ctx, font);
+    let metrics: ExtendedTextMetrics = ctx.measure_text("Mg").unwrap().unchecked_into();
+    let mut height = metrics.font_bounding_box_ascent() + metrics.font_bounding_box_descent();
+    if height.is_nan() || height <= 0.0 {
+        height = metrics.actual_bounding_box_ascent() + metrics.actual_bounding_box_descent();
+    }
+    ctx.restore();
+    height
+}
+
 #[wasm_bindgen]
 extern "C" {
     type ExtendedTextMetrics;
@@ -123,6 +181,12 @@ extern "C" {
     #[wasm_bindgen(method, getter, js_name = actualBoundingBoxRight)]
     fn actual_bounding_box_right(this: &ExtendedTextMetrics) -> f64;
 
+    #[wasm_bindgen(method, getter, js_name = fontBoundingBoxAscent)]
+    fn font_bounding_box_ascent(this: &ExtendedTextMetrics) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = fontBoundingBoxDescent)]
+    fn font_bounding_box_descent(this: &ExtendedTextMetrics) -> f64;
+
     #[wasm_bindgen(method, getter)]
     fn width(this: &ExtendedTextMetrics) -> f64;
 }
@@ -0,0 +1,328 @@
+use crate::grid::locale::*;
+use chrono::format::{Fixed, Item, StrftimeItems};
+use chrono::prelude::*;
+use chrono_tz::Tz;
+use serde_repr::*;
+use std::fmt::Write;
+
+const NANOS_PER_SEC: f64 = 1_000_000_000.0;
+
+/// Unit of the epoch value stored in Date/DateTime/Timestamp columns.
+#[derive(Debug, PartialEq, Serialize_repr, Deserialize_repr, Copy, Clone, Default)]
+#[repr(u32)]
+pub enum EpochUnit {
+    #[default]
+    Millis = 0,
+    Seconds,
+    Micros,
+    Nanos, // f64 keeps ~250ns precision for current dates
+}
+
+impl EpochUnit {
+    pub fn per_second(&self) -> f64 {
+        match self {
+            EpochUnit::Seconds => 1.0,
+            EpochUnit::Millis => 1_000.0,
+            EpochUnit::Micros => 1_000_000.0,
+            EpochUnit::Nanos => NANOS_PER_SEC,
+        }
+    }
+}
+
+/// Parsed `Column.timezone`: "" or "local", "UTC", a fixed offset like "+05:30",
+/// or an IANA name like "America/New_York".
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum Zone {
+    #[default]
+    Local,
+    Utc,
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+impl Zone {
+    pub fn parse(spec: &str) -> Result<Zone, String> {
+        match spec {
+            "" | "local" | "Local" => return Ok(Zone::Local),
+            "UTC" | "utc" | "Z" => return Ok(Zone::Utc),
+            _ => {}
+        }
+        if spec.starts_with('+') || spec.starts_with('-') {
+            return parse_offset(spec)
+                .map(Zone::Fixed)
+                .ok_or_else(|| format!("invalid UTC offset '{}', expected +HH:MM", spec));
+        }
+        spec.parse::<Tz>()
+            .map(Zone::Named)
+            .map_err(|_| format!("unknown timezone '{}'", spec))
+    }
+}
+
+/// "+05:30", "-0800" or "+9" to a fixed offset.
+fn parse_offset(spec: &str) -> Option<FixedOffset> {
+    let sign = if spec.starts_with('-') { -1 } else { 1 };
+    let digits: String = spec[1..].chars().filter(|c| *c != ':').collect();
+    if digits.is_empty() || digits.len() > 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = if digits.len() <= 2 {
+        (digits.parse::<i32>().ok()?, 0)
+    } else {
+        let split = digits.len() - 2;
+        (
+            digits[..split].parse::<i32>().ok()?,
+            digits[split..].parse::<i32>().ok()?,
+        )
+    };
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Source of "now" and of the local zone. The browser clock by default,
+/// tests inject a `FixedClock` so results don't depend on the machine.
+pub trait Clock {
+    fn now_ms(&self) -> f64;
+    fn local_offset(&self, utc: &NaiveDateTime) -> FixedOffset;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> f64 {
+        Utc::now().timestamp_millis() as f64
+    }
+
+    fn local_offset(&self, utc: &NaiveDateTime) -> FixedOffset {
+        Local.offset_from_utc_datetime(utc).fix()
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct FixedClock {
+    pub now_ms: f64,
+    pub offset: FixedOffset,
+}
+
+impl Clock for FixedClock {
+    fn now_ms(&self) -> f64 {
+        self.now_ms
+    }
+
+    fn local_offset(&self, _utc: &NaiveDateTime) -> FixedOffset {
+        self.offset
+    }
+}
+
+/// Epoch value to UTC keeping the sub-second part, `None` when out of range.
+pub fn epoch_to_utc(v: f64, unit: EpochUnit) -> Option<DateTime<Utc>> {
+    if !v.is_finite() {
+        return None;
+    }
+    let per_second = unit.per_second();
+    let mut secs = (v / per_second).floor();
+    let mut nanos = ((v - secs * per_second) * (NANOS_PER_SEC / per_second)).round();
+    if nanos >= NANOS_PER_SEC {
+        secs += 1.0;
+        nanos -= NANOS_PER_SEC;
+    }
+    if secs.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    DateTime::from_timestamp(secs as i64, nanos.max(0.0) as u32)
+}
+
+/// Formats an epoch value in the zone with a chrono strftime format,
+/// `%.3f`/`%.6f`/`%.9f` show fractional seconds, day and month names come from the locale.
+/// Invalid formats fall back to RFC 3339.
+pub fn format_epoch(
+    v: f64,
+    unit: EpochUnit,
+    zone: Zone,
+    format: &str,
+    clock: &dyn Clock,
+    locale: &Locale,
+) -> String {
+    let Some(utc) = epoch_to_utc(v, unit) else {
+        return v.to_string();
+    };
+    match zone {
+        Zone::Utc => write_date(&utc, format, locale),
+        Zone::Fixed(offset) => write_date(&utc.with_timezone(&offset), format, locale),
+        Zone::Named(tz) => write_date(&utc.with_timezone(&tz), format, locale),
+        Zone::Local => {
+            let offset = clock.local_offset(&utc.naive_utc());
+            write_date(&utc.with_timezone(&offset), format, locale)
+        }
+    }
+}
+
+fn write_date<T: TimeZone>(dt: &DateTime<T>, format: &str, locale: &Locale) -> String
+where
+    T::Offset: std::fmt::Display,
+{
+    let items: Vec<Item> = StrftimeItems::new(format)
+        .map(|item| localize_item(item, dt, locale))
+        .collect();
+    let mut out = String::new();
+    // chrono panics on to_string() with a bad format, write! reports it instead
+    if write!(out, "{}", dt.format_with_items(items.iter())).is_err() {
+        out.clear();
+        let _ = write!(out, "{}", dt.format("%Y-%m-%dT%H:%M:%S%.f%:z"));
+    }
+    out
+}
+
+/// Replaces chrono's English names with the locale ones.
+fn localize_item<'a, T: TimeZone>(item: Item<'a>, dt: &DateTime<T>, locale: &Locale) -> Item<'a> {
+    let weekday = dt.weekday().num_days_from_monday() as usize;
+    let month = dt.month0() as usize;
+    let am_pm = locale.am_pm[dt.hour12().0 as usize];
+    match item {
+        Item::Fixed(Fixed::LongMonthName) => Item::Literal(locale.months[month]),
+        Item::Fixed(Fixed::ShortMonthName) => Item::Literal(locale.short_months[month]),
+        Item::Fixed(Fixed::LongWeekdayName) => Item::Literal(locale.days[weekday]),
+        Item::Fixed(Fixed::ShortWeekdayName) => Item::Literal(locale.short_days[weekday]),
+        Item::Fixed(Fixed::UpperAmPm) => Item::Literal(am_pm),
+        Item::Fixed(Fixed::LowerAmPm) => Item::OwnedLiteral(am_pm.to_lowercase().into()),
+        _ => item,
+    }
+}
+
+/// `Column.format` value that shows a date as its age, e.g. "3s ago".
+pub const RELATIVE_FORMAT: &str = "relative";
+
+/// `Column.format` of Duration columns.
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum DurationStyle {
+    #[default]
+    Compact, // "" or "compact": 1.25ms, 2m 5s, 1h 5m
+    Human, // "human": 250 milliseconds, 2 minutes 5 seconds
+}
+
+impl DurationStyle {
+    pub fn parse(spec: &str) -> Result<DurationStyle, String> {
+        match spec {
+            "" | "compact" => Ok(DurationStyle::Compact),
+            "human" => Ok(DurationStyle::Human),
+            _ => Err(format!(
+                "unknown duration format '{}', expected compact or human",
+                spec
+            )),
+        }
+    }
+}
+
+const SUB_SECOND_UNITS: [(f64, &str, &str); 4] = [
+    (NANOS_PER_SEC, "s", "second"),
+    (1_000_000.0, "ms", "millisecond"),
+    (1_000.0, "µs", "microsecond"),
+    (1.0, "ns", "nanosecond"),
+];
+
+const CLOCK_UNITS: [(u64, &str, &str); 4] = [
+    (86_400, "d", "day"),
+    (3_600, "h", "hour"),
+    (60, "m", "minute"),
+    (1, "s", "second"),
+];
+
+/// Formats a duration stored in `unit`, `precision` is the maximum number
+/// of fraction digits below one minute, trailing zeros are dropped.
+pub fn format_duration(v: f64, unit: EpochUnit, style: DurationStyle, precision: usize) -> String {
+    if !v.is_finite() {
+        return v.to_string();
+    }
+    let nanos = v * (NANOS_PER_SEC / unit.per_second());
+    let sign = if nanos < 0.0 { "-" } else { "" };
+    let nanos = nanos.abs();
+    let body = if nanos >= 60.0 * NANOS_PER_SEC {
+        clock_units((nanos / NANOS_PER_SEC).round() as u64, 2, style)
+    } else {
+        sub_second(nanos, precision, style)
+    };
+    if body.chars().all(|c| !c.is_ascii_digit() || c == '0') {
+        body // no "-0ns"
+    } else {
+        [sign, &body].concat()
+    }
+}
+
+/// Age of an epoch value against `now_ms`: "just now", "3s ago", "in 2m".
+pub fn format_relative(v: f64, unit: EpochUnit, now_ms: f64) -> String {
+    if !v.is_finite() {
+        return v.to_string();
+    }
+    let diff_ms = now_ms - v * (1_000.0 / unit.per_second());
+    let secs = (diff_ms.abs() / 1_000.0).floor() as u64;
+    if secs == 0 {
+        return "just now".to_string();
+    }
+    let age = clock_units(secs, 1, DurationStyle::Compact);
+    if diff_ms > 0.0 {
+        format!("{} ago", age)
+    } else {
+        format!("in {}", age)
+    }
+}
+
+/// Up to `max_units` of d/h/m/s starting at the largest non-zero one, e.g. "1h 5m".
+fn clock_units(secs: u64, max_units: usize, style: DurationStyle) -> String {
+    let mut rest = secs;
+    let mut parts = vec![];
+    for (size, short, long) in CLOCK_UNITS.iter() {
+        let n = rest / size;
+        rest %= size;
+        if n == 0 && parts.is_empty() {
+            continue;
+        }
+        if n > 0 {
+            parts.push(unit_text(&n.to_string(), n == 1, short, long, style));
+        }
+        if parts.len() == max_units || n == 0 {
+            break;
+        }
+    }
+    if parts.is_empty() {
+        parts.push(unit_text("0", false, "s", "second", style));
+    }
+    parts.join(" ")
+}
+
+fn sub_second(nanos: f64, precision: usize, style: DurationStyle) -> String {
+    let precision = match style {
+        DurationStyle::Compact => precision,
+        DurationStyle::Human => 0,
+    };
+    let mut index = SUB_SECOND_UNITS
+        .iter()
+        .position(|(size, _, _)| nanos >= *size)
+        .unwrap_or(SUB_SECOND_UNITS.len() - 1);
+    let mut text = fixed(nanos / SUB_SECOND_UNITS[index].0, precision);
+    // 999.999ms rounds up into the next unit
+    if index > 0 && text.parse::<f64>().unwrap_or(0.0) >= 1_000.0 {
+        index -= 1;
+        text = fixed(nanos / SUB_SECOND_UNITS[index].0, precision);
+    }
+    let (_, short, long) = SUB_SECOND_UNITS[index];
+    let one = text == "1";
+    unit_text(&text, one, short, long, style)
+}
+
+fn fixed(v: f64, precision: usize) -> String {
+    let s = format!("{:.*}", precision, v);
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    }
+}
+
+fn unit_text(n: &str, one: bool, short: &str, long: &str, style: DurationStyle) -> String {
+    match style {
+        DurationStyle::Compact => format!("{}{}", n, short),
+        DurationStyle::Human if one => format!("{} {}", n, long),
+        DurationStyle::Human => format!("{} {}s", n, long),
+    }
+}
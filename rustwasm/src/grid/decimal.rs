@@ -0,0 +1,164 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+/// Largest scale we accept - 10^18 is the biggest power of ten that fits into i64.
+pub const MAX_SCALE: u32 = 18;
+
+/// Fixed-point decimal stored as a scaled integer: `value / 10^scale`.
+/// Used for prices that must render without binary float artefacts.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Decimal {
+    pub value: i64,
+    pub scale: u32,
+}
+
+pub const fn pow10(scale: u32) -> i128 {
+    10_i128.pow(scale)
+}
+
+impl Decimal {
+    pub fn new(value: i64, scale: u32) -> Decimal {
+        assert_le!(scale, MAX_SCALE, "decimal scale {} out of range", scale);
+        Decimal { value, scale }
+    }
+
+    pub fn zero(scale: u32) -> Decimal {
+        Decimal::new(0, scale)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    /// Value widened to the given (larger or equal) scale.
+    fn widen(&self, scale: u32) -> i128 {
+        self.value as i128 * pow10(scale - self.scale)
+    }
+
+    /// Value at any scale up to `MAX_SCALE`, rounding half away from zero when digits are dropped.
+    fn scaled(&self, scale: u32) -> i128 {
+        if scale >= self.scale {
+            self.widen(scale)
+        } else {
+            round_div(self.value as i128, pow10(self.scale - scale))
+        }
+    }
+
+    /// Re-scales the value, rounding half away from zero when digits are dropped.
+    /// Saturates at the i64 range.
+    pub fn rescale(&self, scale: u32) -> Decimal {
+        Decimal::new(saturate(self.scaled(scale)), scale)
+    }
+
+    /// Nearest multiple of `step`, half away from zero. A zero step keeps the value.
+    pub fn round_to(&self, step: Decimal) -> Decimal {
+        let scale = std::cmp::max(self.scale, step.scale);
+        let step = step.widen(scale).abs();
+        if step == 0 {
+            return *self;
+        }
+        Decimal::new(saturate(round_div(self.widen(scale), step) * step), scale)
+    }
+
+    /// Lossy conversion, only meant for geometry (bar lengths, sparklines).
+    pub fn to_f64(&self) -> f64 {
+        self.value as f64 / pow10(self.scale) as f64
+    }
+
+    /// Formats the value with exactly `precision` fraction digits.
+    pub fn format(&self, precision: usize) -> String {
+        let precision = std::cmp::min(precision, MAX_SCALE as usize) as u32;
+        let v = self.scaled(precision);
+        let divisor = pow10(precision);
+        let sign = if v < 0 { "-" } else { "" };
+        let int_part = v.abs() / divisor;
+        if precision == 0 {
+            format!("{}{}", sign, int_part)
+        } else {
+            let frac_part = v.abs() % divisor;
+            format!(
+                "{}{}.{:0width$}",
+                sign,
+                int_part,
+                frac_part,
+                width = precision as usize
+            )
+        }
+    }
+}
+
+fn saturate(v: i128) -> i64 {
+    v.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// `v / divisor` rounded half away from zero, `divisor` must be positive.
+pub fn round_div(v: i128, divisor: i128) -> i128 {
+    let q = v / divisor;
+    let r = v % divisor;
+    if r.abs() * 2 >= divisor {
+        q + v.signum()
+    } else {
+        q
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(self.scale as usize);
+        f.pad(&self.format(precision))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = std::cmp::max(self.scale, other.scale);
+        self.widen(scale).cmp(&other.widen(scale))
+    }
+}
+
+/// Saturates at the i64 range, like `rescale`.
+impl Add for Decimal {
+    type Output = Decimal;
+
+    fn add(self, other: Decimal) -> Decimal {
+        let scale = std::cmp::max(self.scale, other.scale);
+        Decimal::new(saturate(self.widen(scale) + other.widen(scale)), scale)
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    fn sub(self, other: Decimal) -> Decimal {
+        self + (-other)
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::new(self.value.saturating_neg(), self.scale)
+    }
+}
+
+impl std::iter::Sum for Decimal {
+    fn sum<I: Iterator<Item = Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::zero(0), |a, b| a + b)
+    }
+}
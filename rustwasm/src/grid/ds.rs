@@ -1,8 +1,13 @@
 use crate::grid::column::*;
+use crate::grid::decimal::*;
 use crate::grid::renderer::*;
+use crate::grid::selection::*;
+use crate::grid::symbols::*;
 use crate::utils::hash_code;
 use byteorder::{BigEndian, ByteOrder};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ops::Range;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -22,14 +27,17 @@ pub struct DataSource<'a> {
     pub data_width: usize,
     pub row_count: usize,
     sparks: Option<&'a Sparks>,
+    symbols: Option<&'a SymbolTable>,
+    strings: &'a [SZ], // side buffer for VarString columns
 }
 
 impl<'a> DataSource<'a> {
     pub fn new(data: &'a [SZ], data_width: usize, sparks: Option<&'a Sparks>) -> DataSource<'a> {
-        assert!(data_width > 0);
-        assert_eq!(
-            data.len() as f64 % data_width as f64,
-            0.0,
+        // callers run validate_layout first, a bad width only costs the trailing rows
+        debug_assert!(data_width > 0);
+        debug_assert_eq!(
+            data.len() % data_width.max(1),
+            0,
             "buffer size {} not divisible by {}",
             data.len(),
             data_width
@@ -38,45 +46,222 @@ impl<'a> DataSource<'a> {
         DataSource {
             data,
             data_width,
-            row_count: (data.len() / data_width),
+            row_count: data.len().checked_div(data_width).unwrap_or(0),
             sparks,
-            ..Default::default()
+            symbols: None,
+            strings: &[],
         }
     }
+
+    pub fn with_strings(mut self, strings: &'a [SZ]) -> DataSource<'a> {
+        self.strings = strings;
+        self
+    }
+
+    pub fn with_symbols(mut self, symbols: &'a SymbolTable) -> DataSource<'a> {
+        self.symbols = Some(symbols);
+        self
+    }
 }
 
 impl<'a> DataSource<'a> {
     pub fn get_value_f64(&self, row: usize, col: &Column) -> Option<f64> {
+        if col.col_type == ColumnType::Decimal {
+            return self.get_value_decimal(row, col).map(|v| v.to_f64());
+        }
         let index = self.get_cell_index(row, col);
         match index {
             Some(i) => {
                 // note: potential performance impact - verify.
                 let v = BigEndian::read_f64(&self.data[i..i + num_size()]);
-                Some(v)
+                match col.null_mode {
+                    NullMode::Sentinel if v.is_nan() => None,
+                    NullMode::Bitmap if !self.is_valid(row, col) => None,
+                    _ => Some(v),
+                }
             }
             _ => None,
         }
     }
 
-    pub fn get_value_str(&self, row: usize, col: &Column) -> Option<String> {
+    pub fn get_value_decimal(&self, row: usize, col: &Column) -> Option<Decimal> {
         let index = self.get_cell_index(row, col);
         match index {
             Some(i) => {
-                let str_slice = &self.data[i..i + col.size];
-                let s = String::from_utf8_lossy(str_slice);
-                Some(String::from(s.trim_end_matches(char::from(0))))
+                let v = BigEndian::read_i64(&self.data[i..i + num_size()]);
+                match col.null_mode {
+                    NullMode::Sentinel if v == DECIMAL_NULL => None,
+                    NullMode::Bitmap if !self.is_valid(row, col) => None,
+                    _ => Some(Decimal::new(v, col.scale)),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Raw string table index of a `Symbol` cell - cheap to compare. A string registered
+    /// twice has two indices, group by `get_symbol_rank` to treat them as one.
+    pub fn get_value_symbol(&self, row: usize, col: &Column) -> Option<u32> {
+        let index = self.get_cell_index(row, col);
+        match index {
+            Some(_) if col.null_mode == NullMode::Bitmap && !self.is_valid(row, col) => None,
+            Some(i) => Some(BigEndian::read_u32(&self.data[i..i + symbol_size()])),
+            None => None,
+        }
+    }
+
+    /// Alphabetical rank of a `Symbol` cell, for sorting and grouping without string
+    /// compares. Equal strings have equal ranks.
+    pub fn get_symbol_rank(&self, row: usize, col: &Column) -> Option<u32> {
+        let i = self.get_value_symbol(row, col)?;
+        self.symbols?.rank(i)
+    }
+
+    /// Borrows the cell text from the row buffer, the side buffer or the string table.
+    pub fn get_value_str(&self, row: usize, col: &Column) -> Option<&'a str> {
+        if col.col_type == ColumnType::Symbol {
+            let i = self.get_value_symbol(row, col)?;
+            return self.symbols?.get(i);
+        }
+        let data: &'a [SZ] = self.data;
+        let index = self.get_cell_index(row, col);
+        match index {
+            Some(_) if col.null_mode == NullMode::Bitmap && !self.is_valid(row, col) => None,
+            Some(i) if col.col_type == ColumnType::VarString => {
+                let offset = BigEndian::read_u32(&data[i..i + 4]) as usize;
+                let len = BigEndian::read_u32(&data[i + 4..i + 8]) as usize;
+                // usize is 32 bits on wasm, a bad pair must not wrap into range
+                let end = offset.checked_add(len)?;
+                self.strings.get(offset..end).map(utf8_prefix)
+            }
+            Some(i) => {
+                let str_slice = &data[i..i + col.size];
+                let len = str_slice.iter().rposition(|&b| b != 0).map_or(0, |p| p + 1);
+                Some(utf8_prefix(&str_slice[..len]))
             }
             None => None, // String::from(""),
         }
     }
-    pub fn get_sparkline(&self, row: usize, col: &Column) -> Option<&Vec<f64>> {
-        if self.sparks.unwrap().len() > 0 {
-            let id = self.get_value_str(row, col);
-            if id.is_some() {
-                return self.sparks.unwrap().get(&hash_code(&id.unwrap()));
+
+    /// Cell text as rendered, `null_text` for nulls. Strings are borrowed from the buffers.
+    pub fn format_cell<'t>(
+        &self,
+        row: usize,
+        col: &Column,
+        fc: &FormatContext,
+        null_text: &'t str,
+    ) -> Option<Cow<'t, str>>
+    where
+        'a: 't,
+    {
+        if self.is_null(row, col) {
+            return Some(Cow::Borrowed(null_text));
+        }
+        match col.col_type {
+            ColumnType::String | ColumnType::Symbol | ColumnType::VarString => {
+                self.get_value_str(row, col).map(Cow::Borrowed)
             }
+            ColumnType::Decimal => col
+                .format_decimal_with(self.get_value_decimal(row, col), fc)
+                .map(Cow::Owned),
+            _ => col
+                .format_value_with(self.get_value_f64(row, col), fc)
+                .map(Cow::Owned),
+        }
+    }
+
+    /// Cell value without formatting: numbers and epochs as stored, decimals at their
+    /// scale, sparklines as space separated values. `None` for nulls.
+    pub fn raw_cell(&self, row: usize, col: &Column) -> Option<Cow<'a, str>> {
+        if self.is_null(row, col) {
+            return None;
+        }
+        match col.col_type {
+            ColumnType::String | ColumnType::Symbol | ColumnType::VarString => {
+                self.get_value_str(row, col).map(Cow::Borrowed)
+            }
+            ColumnType::Sparkline => self.get_sparkline(row, col).map(|values| {
+                Cow::Owned(
+                    values
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                )
+            }),
+            ColumnType::Decimal => self
+                .get_value_decimal(row, col)
+                .map(|d| Cow::Owned(d.to_string())),
+            _ => self
+                .get_value_f64(row, col)
+                .map(|v| Cow::Owned(v.to_string())),
+        }
+    }
+
+    /// Stable identity of a row: the `key` column value, or the row index without one.
+    pub fn row_key(&self, row: usize, key: Option<&Column>) -> Option<RowKey> {
+        let Some(col) = key else {
+            return (row < self.row_count).then_some(RowKey::Number(row as f64));
+        };
+        if self.is_null(row, col) {
+            return None;
+        }
+        match col.col_type {
+            ColumnType::String | ColumnType::Symbol | ColumnType::VarString => self
+                .get_value_str(row, col)
+                .map(|s| RowKey::Text(s.to_string())),
+            ColumnType::Decimal => self
+                .get_value_decimal(row, col)
+                .map(|d| RowKey::Text(d.to_string())),
+            _ => self.get_value_f64(row, col).map(RowKey::Number),
         }
-        None
+    }
+
+    /// True if the cell exists but holds no value.
+    pub fn is_null(&self, row: usize, col: &Column) -> bool {
+        if row >= self.row_count {
+            return false;
+        }
+        match col.null_mode {
+            NullMode::None => false,
+            NullMode::Bitmap => !self.is_valid(row, col),
+            NullMode::Sentinel => match col.col_type {
+                ColumnType::Decimal => self.get_value_decimal(row, col).is_none(),
+                ColumnType::String
+                | ColumnType::Sparkline
+                | ColumnType::Symbol
+                | ColumnType::VarString => false,
+                _ => self.get_value_f64(row, col).is_none(),
+            },
+        }
+    }
+
+    fn is_valid(&self, row: usize, col: &Column) -> bool {
+        let byte = self.data[row * self.data_width + col.validity_index / 8];
+        byte & (1 << (col.validity_index % 8)) != 0
+    }
+
+    /// Non-null values of a numeric column, in row order.
+    pub fn values_f64<'b>(&'b self, col: &'b Column) -> impl Iterator<Item = f64> + 'b {
+        (0..self.row_count).filter_map(move |row| self.get_value_f64(row, col))
+    }
+
+    pub fn max_f64(&self, col: &Column) -> Option<f64> {
+        self.values_f64(col).reduce(f64::max)
+    }
+
+    pub fn sum_decimal(&self, col: &Column) -> Decimal {
+        (0..self.row_count)
+            .filter_map(|row| self.get_value_decimal(row, col))
+            .fold(Decimal::zero(col.scale), |a, b| a + b)
+    }
+
+    pub fn get_sparkline(&self, row: usize, col: &Column) -> Option<&Vec<f64>> {
+        let sparks = self.sparks?;
+        if sparks.is_empty() {
+            return None;
+        }
+        sparks.get(&hash_code(self.get_value_str(row, col)?))
     }
 
     fn get_cell_index(&self, row: usize, col: &Column) -> Option<usize> {
@@ -85,14 +270,71 @@ impl<'a> DataSource<'a> {
             if index < self.data.len() {
                 return Some(index);
             }
-            assert_lt!(
-                index,
-                self.data.len(),
-                "buffer index {} out of bounds {}",
-                index,
-                self.data.len()
-            );
         }
         None
     }
 }
+
+/// Copy of a few buffer rows, e.g. those drawn by the last render, for hit-testing
+/// between renders without keeping the whole buffer. `VarString` bytes come along.
+#[derive(Debug, Default, Clone)]
+pub struct DataWindow {
+    pub first_row: usize, // buffer row of the first copied row
+    data: Vec<SZ>,
+    strings: Vec<SZ>,
+    data_width: usize,
+}
+
+impl DataWindow {
+    pub fn capture(cols: &[Column], ds: &DataSource, rows: Range<usize>) -> DataWindow {
+        let rows = rows.start.min(ds.row_count)..rows.end.min(ds.row_count);
+        let w = ds.data_width;
+        let mut data = ds.data[rows.start * w..rows.end * w].to_vec();
+        let mut strings = Vec::new();
+        for (i, row) in rows.clone().enumerate() {
+            for col in cols.iter().filter(|c| c.col_type == ColumnType::VarString) {
+                let at = i * w + col.data_offset;
+                let (offset, len) = match ds.get_value_str(row, col) {
+                    Some(s) => {
+                        strings.extend_from_slice(s.as_bytes());
+                        ((strings.len() - s.len()) as u32, s.len() as u32)
+                    }
+                    None => (u32::MAX, 0), // stays out of range
+                };
+                BigEndian::write_u32(&mut data[at..at + 4], offset);
+                BigEndian::write_u32(&mut data[at + 4..at + 8], len);
+            }
+        }
+        DataWindow {
+            first_row: rows.start,
+            data,
+            strings,
+            data_width: w,
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.data.len() / self.data_width.max(1)
+    }
+
+    /// Rows are numbered from 0, `first_row` is the buffer row of row 0.
+    pub fn data_source<'a>(
+        &'a self,
+        sparks: Option<&'a Sparks>,
+        symbols: Option<&'a SymbolTable>,
+    ) -> DataSource<'a> {
+        DataSource {
+            symbols,
+            ..DataSource::new(&self.data, self.data_width.max(1), sparks)
+        }
+        .with_strings(&self.strings)
+    }
+}
+
+/// Longest valid UTF-8 prefix - fixed-width producers may cut the last codepoint in half.
+fn utf8_prefix(bytes: &[u8]) -> &str {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(e) => std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default(),
+    }
+}
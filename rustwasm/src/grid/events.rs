@@ -0,0 +1,118 @@
+use crate::error::*;
+use crate::grid::column::*;
+use crate::grid::ds::*;
+use crate::grid::selection::*;
+use crate::grid::state::*;
+use serde::Serialize;
+use serde_json::{Number, Value};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = queueMicrotask)]
+    fn queue_microtask(callback: &JsValue);
+}
+
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum GridEventKind {
+    CellClick = 0,
+    CellDoubleClick = 1,
+    SelectionChange = 2,
+    SortChange = 3,
+    ScrollChange = 4,
+    ColumnLayoutChange = 5,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CellEvent {
+    pub row: RowKey,
+    pub col: String,  // column id
+    pub value: Value, // raw value, see `cell_value`, null for nulls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<&'static str>, // "bid" or "ask" for the order book
+}
+
+/// Callback payloads. Only changes made inside the canvas are reported,
+/// not the ones made by the host through setters.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum GridEvent {
+    CellClick(CellEvent),
+    CellDoubleClick(CellEvent),
+    SelectionChange(Selection),
+    SortChange { sort: Vec<SortKey> },
+    ScrollChange { top_index: usize },
+    ColumnLayoutChange { columns: Vec<ColumnState> }, // display order
+}
+
+impl GridEvent {
+    pub fn kind(&self) -> GridEventKind {
+        match self {
+            GridEvent::CellClick(_) => GridEventKind::CellClick,
+            GridEvent::CellDoubleClick(_) => GridEventKind::CellDoubleClick,
+            GridEvent::SelectionChange(_) => GridEventKind::SelectionChange,
+            GridEvent::SortChange { .. } => GridEventKind::SortChange,
+            GridEvent::ScrollChange { .. } => GridEventKind::ScrollChange,
+            GridEvent::ColumnLayoutChange { .. } => GridEventKind::ColumnLayoutChange,
+        }
+    }
+}
+
+/// Raw value of a cell: numbers and dates as numbers, text as strings,
+/// sparklines as arrays. Decimals are strings with their exact digits,
+/// a JS number would round them.
+pub fn cell_value(ds: &DataSource, row: usize, col: &Column) -> Value {
+    if ds.is_null(row, col) {
+        return Value::Null;
+    }
+    let number = |v: f64| Number::from_f64(v).map_or(Value::Null, Value::Number);
+    match col.col_type {
+        ColumnType::String | ColumnType::Symbol | ColumnType::VarString => ds
+            .get_value_str(row, col)
+            .map_or(Value::Null, |text| Value::String(text.to_string())),
+        ColumnType::Decimal => ds
+            .get_value_decimal(row, col)
+            .map_or(Value::Null, |d| Value::String(d.to_string())),
+        ColumnType::Sparkline => ds.get_sparkline(row, col).map_or(Value::Null, |values| {
+            Value::Array(values.iter().map(|v| number(*v)).collect())
+        }),
+        _ => ds.get_value_f64(row, col).map_or(Value::Null, number),
+    }
+}
+
+/// JS functions registered with `on`, one per event kind.
+#[derive(Debug, Default, Clone)]
+pub struct Callbacks {
+    fns: HashMap<GridEventKind, js_sys::Function>,
+}
+
+impl Callbacks {
+    /// `None` removes the callback.
+    pub fn set(&mut self, kind: GridEventKind, callback: Option<js_sys::Function>) {
+        match callback {
+            Some(callback) => self.fns.insert(kind, callback),
+            None => self.fns.remove(&kind),
+        };
+    }
+
+    pub fn has(&self, kind: GridEventKind) -> bool {
+        self.fns.contains_key(&kind)
+    }
+
+    /// Queues a call of the event kind's callback with the payload as of now.
+    /// It runs as a microtask, once the exported method that emitted the event has
+    /// returned and released the grid, so the callback can call back into it.
+    /// An exception thrown by the callback is reported as uncaught.
+    pub fn emit(&self, event: &GridEvent) -> Result<(), JsValue> {
+        if let Some(callback) = self.fns.get(&event.kind()) {
+            let payload = serde_wasm_bindgen::to_value(event).map_err(GridError::from)?;
+            let callback = callback.clone();
+            queue_microtask(&Closure::once_into_js(move || {
+                callback.call1(&JsValue::NULL, &payload)
+            }));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use crate::grid::clipboard::*;
+use crate::grid::column::*;
+use crate::grid::ds::*;
+use crate::grid::schema::*;
+use std::fmt::Write as _;
+use std::io;
+use std::ops::Range;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ExportFormat {
+    Csv = 0,       // RFC 4180, CRLF line ends
+    JsonLines = 1, // one object per row keyed by column id
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub raw: bool,    // stored values instead of the displayed text
+    pub header: bool, // CSV: column names first
+}
+
+/// Writes every row of `ds`, see `export_rows`.
+pub fn export(
+    out: &mut impl io::Write,
+    schema: &Schema,
+    ds: &DataSource,
+    fc: &FormatContext,
+    options: ExportOptions,
+) -> io::Result<usize> {
+    export_rows(out, schema, ds, 0..ds.row_count, fc, options)
+}
+
+/// Writes the visible columns of `rows` in display order, one line per row, and
+/// returns the number of rows written. Large buffers can be exported in chunks,
+/// the CSV header is only written for a range starting at row 0.
+pub fn export_rows(
+    out: &mut impl io::Write,
+    schema: &Schema,
+    ds: &DataSource,
+    rows: Range<usize>,
+    fc: &FormatContext,
+    options: ExportOptions,
+) -> io::Result<usize> {
+    let cols: Vec<&Column> = schema.get_visible_cols().collect();
+    let rows = rows.start.min(ds.row_count)..rows.end.min(ds.row_count);
+    let null_text = if options.raw { "" } else { &schema.null_text };
+    let mut line = String::new();
+    if options.format == ExportFormat::Csv && options.header && rows.start == 0 {
+        for (i, col) in cols.iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            let _ = write_csv_field(&mut line, &col.name);
+        }
+        line.push_str("\r\n");
+        out.write_all(line.as_bytes())?;
+    }
+    for row in rows.clone() {
+        line.clear();
+        match options.format {
+            ExportFormat::Csv => {
+                for (i, col) in cols.iter().enumerate() {
+                    if i > 0 {
+                        line.push(',');
+                    }
+                    let text = cell_text(ds, row, col, fc, options.raw, null_text);
+                    let _ = write_csv_field(&mut line, &text);
+                }
+                line.push_str("\r\n");
+            }
+            ExportFormat::JsonLines => {
+                line.push('{');
+                for (i, col) in cols.iter().enumerate() {
+                    if i > 0 {
+                        line.push(',');
+                    }
+                    push_json_string(&mut line, &col.id);
+                    line.push(':');
+                    if options.raw {
+                        write_json_value(&mut line, ds, row, col);
+                    } else if ds.is_null(row, col) {
+                        line.push_str("null");
+                    } else {
+                        push_json_string(&mut line, &cell_text(ds, row, col, fc, false, ""));
+                    }
+                }
+                line.push_str("}\n");
+            }
+        }
+        out.write_all(line.as_bytes())?;
+    }
+    Ok(rows.len())
+}
+
+/// JSON of a raw cell value. Numbers stay numbers, decimals keep their exact digits,
+/// sparklines become arrays.
+pub fn write_json_value(line: &mut String, ds: &DataSource, row: usize, col: &Column) {
+    if ds.is_null(row, col) {
+        line.push_str("null");
+        return;
+    }
+    match col.col_type {
+        ColumnType::String | ColumnType::Symbol | ColumnType::VarString => {
+            match ds.get_value_str(row, col) {
+                Some(text) => push_json_string(line, text),
+                None => line.push_str("null"),
+            }
+        }
+        ColumnType::Decimal => match ds.get_value_decimal(row, col) {
+            Some(d) => {
+                let _ = write!(line, "{}", d);
+            }
+            None => line.push_str("null"),
+        },
+        ColumnType::Sparkline => match ds.get_sparkline(row, col) {
+            Some(values) => {
+                line.push('[');
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        line.push(',');
+                    }
+                    push_json_number(line, *v);
+                }
+                line.push(']');
+            }
+            None => line.push_str("null"),
+        },
+        _ => match ds.get_value_f64(row, col) {
+            Some(v) => push_json_number(line, v),
+            None => line.push_str("null"),
+        },
+    }
+}
+
+fn push_json_number(line: &mut String, v: f64) {
+    if v.is_finite() {
+        let _ = write!(line, "{}", v);
+    } else {
+        line.push_str("null");
+    }
+}
+
+fn push_json_string(line: &mut String, text: &str) {
+    line.push_str(&serde_json::to_string(text).unwrap_or_default());
+}
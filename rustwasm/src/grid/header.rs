@@ -0,0 +1,56 @@
+use crate::grid::column::*;
+
+/// One header cell: a group spanning adjacent columns on one line, or a column
+/// name spanning the lines below its innermost group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderCell<'a> {
+    pub text: &'a str,
+    pub line: usize,  // first header line
+    pub lines: usize, // header lines covered
+    pub first: usize, // first visible column
+    pub last: usize,  // last visible column, inclusive
+}
+
+/// Lines needed for the deepest group path, at least one.
+pub fn header_lines<'a>(cols: impl Iterator<Item = &'a Column>) -> usize {
+    1 + cols.map(|col| col.group.len()).max().unwrap_or_default()
+}
+
+/// Header cells of the visible columns in display order, line by line.
+/// Adjacent columns with the same group path up to a line share its cell.
+pub fn header_cells<'a>(cols: &[&'a Column], lines: usize) -> Vec<HeaderCell<'a>> {
+    let mut cells = Vec::new();
+    for line in 0..lines.saturating_sub(1) {
+        let mut first = 0;
+        while first < cols.len() {
+            let path = &cols[first].group;
+            let mut last = first;
+            if path.len() > line {
+                while cols.get(last + 1).is_some_and(|next| {
+                    next.group.len() > line && next.group[..=line] == path[..=line]
+                }) {
+                    last += 1;
+                }
+                cells.push(HeaderCell {
+                    text: &path[line],
+                    line,
+                    lines: 1,
+                    first,
+                    last,
+                });
+            }
+            first = last + 1;
+        }
+    }
+    for (index, col) in cols.iter().enumerate() {
+        let line = col.group.len().min(lines.saturating_sub(1));
+        cells.push(HeaderCell {
+            text: &col.name,
+            line,
+            lines: lines - line,
+            first: index,
+            last: index,
+        });
+    }
+    cells
+}
@@ -0,0 +1,199 @@
+use crate::grid::renderer::*;
+use crate::grid::schema::*;
+use byteorder::{BigEndian, ByteOrder};
+use std::fmt;
+
+/// "GRID" - first bytes of a buffer that carries a layout header.
+pub const HEADER_MAGIC: u32 = 0x4752_4944;
+pub const LAYOUT_VERSION: u32 = 1;
+/// magic, layout version, row count, schema hash - u32 each, big endian.
+pub const HEADER_SIZE: usize = 16;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct BufferHeader {
+    pub magic: u32,
+    pub version: u32,
+    pub row_count: u32,
+    pub schema_hash: u32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LayoutError {
+    ZeroDataWidth,
+    BufferTooSmall { len: usize, expected: usize },
+    BadMagic(u32),
+    VersionMismatch { found: u32, expected: u32 },
+    SchemaMismatch { found: u32, expected: u32 },
+    DataWidthMismatch { found: usize, expected: usize },
+    UnalignedBuffer { len: usize, data_width: usize },
+    RowCountOverflow { row_count: u32, data_width: usize }, // header rows don't fit in memory
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::ZeroDataWidth => write!(f, "data width must be greater than zero"),
+            LayoutError::BufferTooSmall { len, expected } => write!(
+                f,
+                "buffer of {} bytes is too small, expected at least {}",
+                len, expected
+            ),
+            LayoutError::BadMagic(magic) => write!(
+                f,
+                "buffer header magic {:#010x} does not match {:#010x}",
+                magic, HEADER_MAGIC
+            ),
+            LayoutError::VersionMismatch { found, expected } => write!(
+                f,
+                "buffer layout version {} is not supported, expected {}",
+                found, expected
+            ),
+            LayoutError::SchemaMismatch { found, expected } => write!(
+                f,
+                "buffer schema hash {:#010x} does not match the grid schema {:#010x} - producer and consumer schemas differ",
+                found, expected
+            ),
+            LayoutError::DataWidthMismatch { found, expected } => write!(
+                f,
+                "data width {} does not match the schema row width {}",
+                found, expected
+            ),
+            LayoutError::UnalignedBuffer { len, data_width } => write!(
+                f,
+                "buffer size {} not divisible by data width {}",
+                len, data_width
+            ),
+            LayoutError::RowCountOverflow {
+                row_count,
+                data_width,
+            } => write!(
+                f,
+                "buffer header row count {} of {} bytes each overflows the address space",
+                row_count, data_width
+            ),
+        }
+    }
+}
+
+impl BufferHeader {
+    pub fn new(schema: &Schema, row_count: usize) -> BufferHeader {
+        BufferHeader {
+            magic: HEADER_MAGIC,
+            version: LAYOUT_VERSION,
+            row_count: row_count as u32,
+            schema_hash: schema.layout_hash,
+        }
+    }
+
+    pub fn read(data: &[SZ]) -> Result<BufferHeader, LayoutError> {
+        if data.len() < HEADER_SIZE {
+            return Err(LayoutError::BufferTooSmall {
+                len: data.len(),
+                expected: HEADER_SIZE,
+            });
+        }
+        Ok(BufferHeader {
+            magic: BigEndian::read_u32(&data[0..4]),
+            version: BigEndian::read_u32(&data[4..8]),
+            row_count: BigEndian::read_u32(&data[8..12]),
+            schema_hash: BigEndian::read_u32(&data[12..16]),
+        })
+    }
+
+    pub fn write(&self, data: &mut [SZ]) {
+        BigEndian::write_u32(&mut data[0..4], self.magic);
+        BigEndian::write_u32(&mut data[4..8], self.version);
+        BigEndian::write_u32(&mut data[8..12], self.row_count);
+        BigEndian::write_u32(&mut data[12..16], self.schema_hash);
+    }
+}
+
+/// FNV-1a over the column types, offsets, sizes and scales computed by `normalize_schema`.
+/// Kept trivial so the TS producer can compute the same value.
+pub fn schema_hash(schema: &Schema) -> u32 {
+    const FNV_OFFSET: u32 = 0x811c_9dc5;
+    const FNV_PRIME: u32 = 0x0100_0193;
+    let mut hash = FNV_OFFSET;
+    let mut feed = |v: u32| {
+        for b in v.to_be_bytes().iter() {
+            hash ^= *b as u32;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+    feed(schema.validity_size as u32);
+    for col in &schema.cols {
+        feed(col.col_type as u32);
+        feed(col.data_offset as u32);
+        feed(col.size as u32);
+        feed(col.scale);
+    }
+    hash
+}
+
+/// Checks the buffer against the schema and returns the row payload.
+/// With `schema.buffer_header` set the header is validated and stripped,
+/// and the payload is cut to the declared row count.
+pub fn validate_layout<'a>(
+    schema: &Schema,
+    data: &'a [SZ],
+    data_width: usize,
+) -> Result<&'a [SZ], LayoutError> {
+    if data_width == 0 {
+        return Err(LayoutError::ZeroDataWidth);
+    }
+
+    let payload = if schema.buffer_header {
+        let header = BufferHeader::read(data)?;
+        if header.magic != HEADER_MAGIC {
+            return Err(LayoutError::BadMagic(header.magic));
+        }
+        if header.version != LAYOUT_VERSION {
+            return Err(LayoutError::VersionMismatch {
+                found: header.version,
+                expected: LAYOUT_VERSION,
+            });
+        }
+        if header.schema_hash != schema.layout_hash {
+            return Err(LayoutError::SchemaMismatch {
+                found: header.schema_hash,
+                expected: schema.layout_hash,
+            });
+        }
+        if data_width != schema.data_width {
+            return Err(LayoutError::DataWidthMismatch {
+                found: data_width,
+                expected: schema.data_width,
+            });
+        }
+        let expected = (header.row_count as usize)
+            .checked_mul(data_width)
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .ok_or(LayoutError::RowCountOverflow {
+                row_count: header.row_count,
+                data_width,
+            })?;
+        if data.len() < expected {
+            return Err(LayoutError::BufferTooSmall {
+                len: data.len(),
+                expected,
+            });
+        }
+        &data[HEADER_SIZE..expected]
+    } else {
+        data
+    };
+
+    if payload.len() % data_width != 0 {
+        return Err(LayoutError::UnalignedBuffer {
+            len: payload.len(),
+            data_width,
+        });
+    }
+    if data_width < schema.data_width {
+        return Err(LayoutError::DataWidthMismatch {
+            found: data_width,
+            expected: schema.data_width,
+        });
+    }
+    Ok(payload)
+}
@@ -0,0 +1,277 @@
+/// Separators and date names for one locale. Built in, so formatting
+/// doesn't depend on the browser's `Intl` and behaves the same in tests.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Locale {
+    pub tag: &'static str,
+    pub decimal: char,
+    pub group: char,
+    pub months: [&'static str; 12],
+    pub short_months: [&'static str; 12],
+    pub days: [&'static str; 7], // Monday first
+    pub short_days: [&'static str; 7],
+    pub am_pm: [&'static str; 2],
+}
+
+impl Default for Locale {
+    fn default() -> Locale {
+        EN_US
+    }
+}
+
+const EN_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const EN_SHORT_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const EN_DAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+const EN_SHORT_DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const AM_PM: [&str; 2] = ["AM", "PM"];
+
+const DE_MONTHS: [&str; 12] = [
+    "Januar",
+    "Februar",
+    "März",
+    "April",
+    "Mai",
+    "Juni",
+    "Juli",
+    "August",
+    "September",
+    "Oktober",
+    "November",
+    "Dezember",
+];
+const DE_SHORT_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+];
+const DE_DAYS: [&str; 7] = [
+    "Montag",
+    "Dienstag",
+    "Mittwoch",
+    "Donnerstag",
+    "Freitag",
+    "Samstag",
+    "Sonntag",
+];
+const DE_SHORT_DAYS: [&str; 7] = ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"];
+
+pub const EN_US: Locale = Locale {
+    tag: "en-US",
+    decimal: '.',
+    group: ',',
+    months: EN_MONTHS,
+    short_months: EN_SHORT_MONTHS,
+    days: EN_DAYS,
+    short_days: EN_SHORT_DAYS,
+    am_pm: AM_PM,
+};
+
+pub const EN_GB: Locale = Locale {
+    tag: "en-GB",
+    ..EN_US
+};
+
+pub const DE_DE: Locale = Locale {
+    tag: "de-DE",
+    decimal: ',',
+    group: '.',
+    months: DE_MONTHS,
+    short_months: DE_SHORT_MONTHS,
+    days: DE_DAYS,
+    short_days: DE_SHORT_DAYS,
+    am_pm: AM_PM,
+};
+
+pub const DE_CH: Locale = Locale {
+    tag: "de-CH",
+    decimal: '.',
+    group: '’',
+    ..DE_DE
+};
+
+pub const FR_FR: Locale = Locale {
+    tag: "fr-FR",
+    decimal: ',',
+    group: '\u{202f}', // narrow no-break space
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    short_months: [
+        "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.",
+        "déc.",
+    ],
+    days: [
+        "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+    ],
+    short_days: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+    am_pm: AM_PM,
+};
+
+pub const ES_ES: Locale = Locale {
+    tag: "es-ES",
+    decimal: ',',
+    group: '.',
+    months: [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    short_months: [
+        "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+    ],
+    days: [
+        "lunes",
+        "martes",
+        "miércoles",
+        "jueves",
+        "viernes",
+        "sábado",
+        "domingo",
+    ],
+    short_days: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    am_pm: AM_PM,
+};
+
+pub const IT_IT: Locale = Locale {
+    tag: "it-IT",
+    decimal: ',',
+    group: '.',
+    months: [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+    short_months: [
+        "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+    ],
+    days: [
+        "lunedì",
+        "martedì",
+        "mercoledì",
+        "giovedì",
+        "venerdì",
+        "sabato",
+        "domenica",
+    ],
+    short_days: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+    am_pm: AM_PM,
+};
+
+pub const PT_BR: Locale = Locale {
+    tag: "pt-BR",
+    decimal: ',',
+    group: '.',
+    months: [
+        "janeiro",
+        "fevereiro",
+        "março",
+        "abril",
+        "maio",
+        "junho",
+        "julho",
+        "agosto",
+        "setembro",
+        "outubro",
+        "novembro",
+        "dezembro",
+    ],
+    short_months: [
+        "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez",
+    ],
+    days: [
+        "segunda-feira",
+        "terça-feira",
+        "quarta-feira",
+        "quinta-feira",
+        "sexta-feira",
+        "sábado",
+        "domingo",
+    ],
+    short_days: ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+    am_pm: AM_PM,
+};
+
+pub const LOCALES: [Locale; 8] = [EN_US, EN_GB, DE_DE, DE_CH, FR_FR, ES_ES, IT_IT, PT_BR];
+
+impl Locale {
+    /// Looks up a BCP 47 tag like "de-DE" or "de_DE", a bare language like "fr"
+    /// picks its first locale, "" is en-US.
+    pub fn find(tag: &str) -> Result<Locale, String> {
+        if tag.is_empty() {
+            return Ok(EN_US);
+        }
+        let tag = tag.replace('_', "-");
+        let language = tag.split('-').next().unwrap_or_default();
+        LOCALES
+            .iter()
+            .find(|l| l.tag.eq_ignore_ascii_case(&tag))
+            .or_else(|| {
+                LOCALES.iter().find(|l| {
+                    l.tag
+                        .split('-')
+                        .next()
+                        .is_some_and(|lang| lang.eq_ignore_ascii_case(language))
+                })
+            })
+            .copied()
+            .ok_or_else(|| format!("unsupported locale '{}'", tag))
+    }
+
+    /// Swaps the '.' of an already formatted number for the locale decimal separator.
+    pub fn localize_decimal(&self, text: String) -> String {
+        if self.decimal == '.' {
+            text
+        } else {
+            text.replace('.', self.decimal.encode_utf8(&mut [0; 4]))
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use serde_repr::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+pub const ELLIPSIS: &str = "…";
+pub const NUMERIC_OVERFLOW: &str = "###";
+/// Entries per cache generation, at most twice as many are kept.
+pub const GENERATION_SIZE: usize = 10_000;
+
+/// What happens to text wider than its cell.
+#[derive(Debug, PartialEq, Serialize_repr, Deserialize_repr, Copy, Clone, Default)]
+#[repr(u32)]
+pub enum Overflow {
+    #[default]
+    Ellipsis = 0,
+    Clip, // drawn whole, clipped at the cell border
+}
+
+/// Text widths per font, kept across renders. Generational so streaming values
+/// can't grow it forever: when the current generation is full the previous one
+/// is dropped, and widths still in use are carried over on their next lookup.
+#[derive(Debug, Default)]
+pub struct MeasureCache {
+    current: Generation,
+    previous: Generation,
+}
+
+#[derive(Debug, Default)]
+struct Generation {
+    widths: HashMap<String, HashMap<String, f64>>,
+    len: usize,
+}
+
+impl Generation {
+    fn get(&self, font: &str, text: &str) -> Option<f64> {
+        self.widths.get(font)?.get(text).copied()
+    }
+
+    fn remove(&mut self, font: &str, text: &str) -> Option<f64> {
+        let w = self.widths.get_mut(font)?.remove(text)?;
+        self.len -= 1;
+        Some(w)
+    }
+
+    fn insert(&mut self, font: &str, text: &str, w: f64) {
+        self.widths
+            .entry(font.to_string())
+            .or_default()
+            .insert(text.to_string(), w);
+        self.len += 1;
+    }
+}
+
+impl MeasureCache {
+    pub fn new() -> MeasureCache {
+        MeasureCache::default()
+    }
+
+    pub fn measure(&mut self, font: &str, text: &str, f: impl FnOnce(&str) -> f64) -> f64 {
+        if let Some(w) = self.current.get(font, text) {
+            return w;
+        }
+        let w = match self.previous.remove(font, text) {
+            Some(w) => w,
+            None => f(text),
+        };
+        if self.current.len >= GENERATION_SIZE {
+            self.previous = std::mem::take(&mut self.current);
+        }
+        self.current.insert(font, text, w);
+        w
+    }
+
+    pub fn len(&self) -> usize {
+        self.current.len + self.previous.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.current = Generation::default();
+        self.previous = Generation::default();
+    }
+}
+
+/// Fits text into `max_width`: numbers that don't fit become `###`,
+/// other text is cut at a char boundary and gets an ellipsis.
+pub fn fit_text<'t>(
+    text: &'t str,
+    max_width: f64,
+    numeric: bool,
+    measure: &mut dyn FnMut(&str) -> f64,
+) -> Cow<'t, str> {
+    if text.is_empty() || measure(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    if numeric {
+        return if measure(NUMERIC_OVERFLOW) <= max_width {
+            Cow::Borrowed(NUMERIC_OVERFLOW)
+        } else {
+            Cow::Borrowed("")
+        };
+    }
+    // longest prefix that fits with the ellipsis
+    let bounds: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let (mut lo, mut hi) = (0, bounds.len() - 1);
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        let candidate = [text[..bounds[mid]].trim_end(), ELLIPSIS].concat();
+        if measure(&candidate) <= max_width {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    if lo == 0 {
+        return if measure(ELLIPSIS) <= max_width {
+            Cow::Borrowed(ELLIPSIS)
+        } else {
+            Cow::Borrowed("")
+        };
+    }
+    Cow::Owned([text[..bounds[lo]].trim_end(), ELLIPSIS].concat())
+}
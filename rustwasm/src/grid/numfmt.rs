@@ -0,0 +1,282 @@
+use crate::grid::decimal::*;
+use crate::grid::locale::*;
+
+/// How the sign is shown.
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum Sign {
+    #[default]
+    Negative, // "-" for negatives only
+    Always, // "+" for positives and zero, "-" for negatives
+    Parens, // negatives wrapped in parentheses
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum FormatKind {
+    #[default]
+    Fixed, // "f" - precision is the number of fraction digits
+    Significant, // "g" - precision is the number of significant digits
+    Percent,     // "%" - value * 100 with a % suffix
+    BasisPoints, // "b" - value * 10000 with a bp suffix
+    Abbreviated, // "a" - K/M/B/T units
+}
+
+/// Parsed `Column.format` for numbers, a d3-like spec: `[sign][,][.precision][type]`
+/// where sign is `+` or `(`, `,` turns on grouping and type is one of `f g % b a`.
+/// Examples: `,.2f` 1,234.50, `+.1%` +12.5%, `(,.0f` (1,235), `.1a` 1.2M, `.3g` 0.00123
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub struct NumberFormat {
+    pub sign: Sign,
+    pub grouping: bool,
+    pub precision: Option<usize>, // None - use Column.precision
+    pub kind: FormatKind,
+}
+
+const UNITS: [(i32, &str); 4] = [(12, "T"), (9, "B"), (6, "M"), (3, "K")];
+
+/// Sign, digits and unit of a rounded number, before separators and affixes are applied.
+struct Parts {
+    negative: bool,
+    int_digits: String,
+    frac_digits: String,
+    unit: &'static str,
+}
+
+impl NumberFormat {
+    pub const DEFAULT: NumberFormat = NumberFormat {
+        sign: Sign::Negative,
+        grouping: false,
+        precision: None,
+        kind: FormatKind::Fixed,
+    };
+
+    pub fn parse(spec: &str) -> Result<NumberFormat, String> {
+        let mut nf = NumberFormat::DEFAULT;
+        let mut rest = spec;
+
+        if let Some(r) = rest.strip_prefix('+') {
+            nf.sign = Sign::Always;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('(') {
+            nf.sign = Sign::Parens;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('-') {
+            rest = r;
+        }
+        if let Some(r) = rest.strip_prefix(',') {
+            nf.grouping = true;
+            rest = r;
+        }
+        if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(|c: char| !c.is_ascii_digit()).unwrap_or(r.len());
+            if end == 0 {
+                return Err(format!("missing precision after '.' in '{}'", spec));
+            }
+            nf.precision = Some(
+                r[..end]
+                    .parse()
+                    .map_err(|_| format!("invalid precision in '{}'", spec))?,
+            );
+            rest = &r[end..];
+        }
+        nf.kind = match rest {
+            "" | "f" => FormatKind::Fixed,
+            "g" => FormatKind::Significant,
+            "%" => FormatKind::Percent,
+            "b" => FormatKind::BasisPoints,
+            "a" => FormatKind::Abbreviated,
+            _ => return Err(format!("unknown number format '{}'", spec)),
+        };
+        if nf.kind == FormatKind::Significant && nf.precision == Some(0) {
+            return Err(format!("significant digits must be > 0 in '{}'", spec));
+        }
+        Ok(nf)
+    }
+
+    pub fn format_f64(
+        &self,
+        v: f64,
+        precision: usize,
+        prefix: &str,
+        suffix: &str,
+        locale: &Locale,
+    ) -> String {
+        if !v.is_finite() {
+            return v.to_string();
+        }
+        let precision = self.precision.unwrap_or(precision);
+        let (v, unit) = match self.kind {
+            FormatKind::Percent => (v * 100.0, "%"),
+            FormatKind::BasisPoints => (v * 10_000.0, "bp"),
+            FormatKind::Abbreviated => {
+                let (exp, unit) = abbreviation(v.abs().log10().floor() as i32);
+                (v / 10_f64.powi(exp), unit)
+            }
+            _ => (v, ""),
+        };
+        let parts = match self.kind {
+            FormatKind::Significant => significant_parts(v, std::cmp::max(1, precision)),
+            _ => split_parts(&format!("{:.*}", precision, v)),
+        };
+        self.finish(Parts { unit, ..parts }, prefix, suffix, locale)
+    }
+
+    /// Same as `format_f64` but exact - decimals never go through f64.
+    /// Significant digits are not supported for decimals and fall back to fixed.
+    pub fn format_decimal(
+        &self,
+        v: Decimal,
+        precision: usize,
+        prefix: &str,
+        suffix: &str,
+        locale: &Locale,
+    ) -> String {
+        let precision = self.precision.unwrap_or(precision);
+        let (v, unit) = match self.kind {
+            FormatKind::Percent | FormatKind::BasisPoints => {
+                let (n, unit) = match self.kind {
+                    FormatKind::Percent => (2, "%"),
+                    _ => (4, "bp"),
+                };
+                match shift(v, n) {
+                    Some(v) => (v, unit),
+                    // too large for i64 once scaled, f64 keeps the magnitude
+                    None => return self.format_f64(v.to_f64(), precision, prefix, suffix, locale),
+                }
+            }
+            FormatKind::Abbreviated => {
+                let int_part = (v.value as i128).abs() / pow10(v.scale);
+                let magnitude = if int_part == 0 {
+                    0
+                } else {
+                    int_part.to_string().len() as i32 - 1
+                };
+                let (exp, unit) = abbreviation(magnitude);
+                (unshift(v, exp as u32), unit)
+            }
+            _ => (v, ""),
+        };
+        let parts = split_parts(&v.format(precision));
+        self.finish(Parts { unit, ..parts }, prefix, suffix, locale)
+    }
+
+    fn finish(&self, parts: Parts, prefix: &str, suffix: &str, locale: &Locale) -> String {
+        let is_zero = parts
+            .int_digits
+            .chars()
+            .chain(parts.frac_digits.chars())
+            .all(|c| c == '0');
+        // rounding to zero drops the sign, like -0.001 with 2 digits
+        let negative = parts.negative && !is_zero;
+
+        let int_part = if self.grouping {
+            group(&parts.int_digits, locale.group)
+        } else {
+            parts.int_digits
+        };
+        let mut body = String::with_capacity(int_part.len() + parts.frac_digits.len() + 8);
+        body.push_str(prefix);
+        body.push_str(&int_part);
+        if !parts.frac_digits.is_empty() {
+            body.push(locale.decimal);
+            body.push_str(&parts.frac_digits);
+        }
+        body.push_str(parts.unit);
+        body.push_str(suffix);
+
+        match (negative, self.sign) {
+            (true, Sign::Parens) => format!("({})", body),
+            (true, _) => format!("-{}", body),
+            (false, Sign::Always) => format!("+{}", body),
+            _ => body,
+        }
+    }
+}
+
+fn abbreviation(magnitude: i32) -> (i32, &'static str) {
+    UNITS
+        .iter()
+        .find(|(exp, _)| magnitude >= *exp)
+        .copied()
+        .unwrap_or((0, ""))
+}
+
+/// Multiplies by 10^n without going through f64, `None` when the result overflows i64.
+fn shift(v: Decimal, n: u32) -> Option<Decimal> {
+    if v.scale >= n {
+        Some(Decimal::new(v.value, v.scale - n))
+    } else {
+        let value = v.value.checked_mul(pow10(n - v.scale) as i64)?;
+        Some(Decimal::new(value, 0))
+    }
+}
+
+/// Divides by 10^n, dropping digits beyond MAX_SCALE.
+fn unshift(v: Decimal, n: u32) -> Decimal {
+    let v = if v.scale + n > MAX_SCALE {
+        v.rescale(MAX_SCALE - n)
+    } else {
+        v
+    };
+    Decimal::new(v.value, v.scale + n)
+}
+
+fn split_parts(s: &str) -> Parts {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(d) => (true, d),
+        None => (false, s),
+    };
+    let (int_digits, frac_digits) = match digits.find('.') {
+        Some(p) => (&digits[..p], &digits[p + 1..]),
+        None => (digits, ""),
+    };
+    Parts {
+        negative,
+        int_digits: int_digits.to_string(),
+        frac_digits: frac_digits.to_string(),
+        unit: "",
+    }
+}
+
+/// Positional rendering with `digits` significant digits, e.g. (0.0012345, 3) => 0.00123
+fn significant_parts(v: f64, digits: usize) -> Parts {
+    let s = format!("{:.*e}", digits - 1, v.abs());
+    let (mantissa, exp) = s.split_at(s.find('e').unwrap_or(s.len()));
+    let exp: i32 = exp.trim_start_matches('e').parse().unwrap_or(0);
+    let mantissa: String = mantissa.chars().filter(|c| *c != '.').collect();
+
+    let (int_digits, frac_digits) = if exp < 0 {
+        let zeros = "0".repeat((-exp - 1) as usize);
+        ("0".to_string(), zeros + &mantissa)
+    } else {
+        let int_len = exp as usize + 1;
+        if int_len >= mantissa.len() {
+            (
+                mantissa.clone() + &"0".repeat(int_len - mantissa.len()),
+                String::new(),
+            )
+        } else {
+            (
+                mantissa[..int_len].to_string(),
+                mantissa[int_len..].to_string(),
+            )
+        }
+    };
+    Parts {
+        negative: v < 0.0,
+        int_digits,
+        frac_digits,
+        unit: "",
+    }
+}
+
+pub fn group(digits: &str, separator: char) -> String {
+    let len = digits.len();
+    let mut out = String::with_capacity(len + len / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (len - i).is_multiple_of(3) {
+            out.push(separator);
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,237 @@
+use crate::grid::schema::*;
+
+pub const RESIZE_HOTSPOT: f64 = 4.0; // px either side of a header border
+pub const DRAG_THRESHOLD: f64 = 4.0; // px before a press on a header becomes a move
+pub const MIN_RESIZE_WIDTH: f64 = 30.0;
+
+/// Header geometry of the last render, in canvas pixels.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeaderLayout {
+    pub top: f64,
+    pub bottom: f64,
+    pub edges: Vec<f64>, // left edge of each visible column and the right edge of the last
+    pub ids: Vec<String>, // visible column ids
+    pub row_height: f64, // of the data rows below `bottom`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeaderHit {
+    Border(usize), // right border of a visible column
+    Cell(usize),
+}
+
+impl HeaderLayout {
+    pub fn hit(&self, x: f64, y: f64) -> Option<HeaderHit> {
+        if y < self.top || y > self.bottom || self.ids.is_empty() {
+            return None;
+        }
+        // borders win over cells, the first column's left edge can't be dragged
+        if let Some(border) =
+            (1..self.edges.len()).find(|&i| (x - self.edges[i]).abs() <= RESIZE_HOTSPOT)
+        {
+            return Some(HeaderHit::Border(border - 1));
+        }
+        self.col_at(x).map(HeaderHit::Cell)
+    }
+
+    /// Data row below the header, counted from the first one drawn, and visible column.
+    pub fn data_cell_at(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        if y < self.bottom || self.row_height <= 0.0 {
+            return None;
+        }
+        let col = self.col_at(x)?;
+        Some((((y - self.bottom) / self.row_height) as usize, col))
+    }
+
+    /// Visible column under `x`, at any height.
+    pub fn col_at(&self, x: f64) -> Option<usize> {
+        (0..self.ids.len()).find(|&i| x >= self.edges[i] && x < self.edges[i + 1])
+    }
+
+    /// Insertion slot, 0..=ids.len(), nearest to `x`.
+    pub fn slot(&self, x: f64) -> usize {
+        (0..self.ids.len())
+            .find(|&i| x < (self.edges[i] + self.edges[i + 1]) / 2.0)
+            .unwrap_or(self.ids.len())
+    }
+
+    fn width(&self, col: usize) -> f64 {
+        self.edges[col + 1] - self.edges[col]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Drag {
+    #[default]
+    Idle,
+    Pressed {
+        col: usize,
+        x: f64,
+    },
+    Resizing {
+        col: usize,
+        start_x: f64,
+        start_width: f64,
+    },
+    Moving {
+        col: usize,
+        slot: usize,
+    },
+}
+
+/// Layout change requested by the pointer, applied by the owner of the schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerAction {
+    None,
+    Redraw, // drag feedback changed
+    Resize { id: String, width: f64 },
+    Move { id: String, slot: usize }, // see `drop_position`
+    Click { id: String },             // released without dragging
+}
+
+/// What to draw on top of the grid while dragging.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Guide {
+    Resize(f64), // x of the border being dragged, full height
+    Drop(f64),   // x of the insertion slot, header only
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PointerState {
+    pub drag: Drag,
+    guide_x: f64,
+}
+
+impl PointerState {
+    /// True when the press starts a header drag and the host should capture the pointer.
+    pub fn down(&mut self, layout: &HeaderLayout, x: f64, y: f64) -> bool {
+        self.drag = match layout.hit(x, y) {
+            Some(HeaderHit::Border(col)) => {
+                self.guide_x = x;
+                Drag::Resizing {
+                    col,
+                    start_x: x,
+                    start_width: layout.width(col),
+                }
+            }
+            Some(HeaderHit::Cell(col)) => Drag::Pressed { col, x },
+            None => Drag::Idle,
+        };
+        self.drag != Drag::Idle
+    }
+
+    pub fn move_to(&mut self, layout: &HeaderLayout, x: f64) -> PointerAction {
+        match self.drag {
+            Drag::Idle => PointerAction::None,
+            Drag::Pressed { col, x: start } => {
+                if (x - start).abs() < DRAG_THRESHOLD {
+                    return PointerAction::None;
+                }
+                self.drag = Drag::Moving {
+                    col,
+                    slot: layout.slot(x),
+                };
+                PointerAction::Redraw
+            }
+            Drag::Resizing {
+                col,
+                start_x,
+                start_width,
+            } => {
+                let width = (start_width + x - start_x).max(MIN_RESIZE_WIDTH).round();
+                // the layout may have lost the column since the press, e.g. hidden by the host
+                match (layout.edges.get(col), layout.ids.get(col)) {
+                    (Some(left), Some(id)) => {
+                        self.guide_x = left + width;
+                        PointerAction::Resize {
+                            id: id.to_string(),
+                            width,
+                        }
+                    }
+                    _ => PointerAction::None,
+                }
+            }
+            Drag::Moving { col, slot } => {
+                let next = layout.slot(x);
+                if next == slot {
+                    return PointerAction::None;
+                }
+                self.drag = Drag::Moving { col, slot: next };
+                PointerAction::Redraw
+            }
+        }
+    }
+
+    pub fn up(&mut self, layout: &HeaderLayout, x: f64) -> PointerAction {
+        let action = match self.move_to(layout, x) {
+            PointerAction::Resize { id, width } => PointerAction::Resize { id, width },
+            _ => match self.drag {
+                Drag::Moving { col, slot } if slot != col && slot != col + 1 => {
+                    match layout.ids.get(col) {
+                        Some(id) => PointerAction::Move {
+                            id: id.to_string(),
+                            slot,
+                        },
+                        None => PointerAction::Redraw,
+                    }
+                }
+                Drag::Moving { .. } => PointerAction::Redraw, // drop indicator goes away
+                Drag::Pressed { col, .. } => match layout.ids.get(col) {
+                    Some(id) => PointerAction::Click { id: id.to_string() },
+                    None => PointerAction::None,
+                },
+                _ => PointerAction::None,
+            },
+        };
+        self.drag = Drag::Idle;
+        action
+    }
+
+    pub fn cancel(&mut self) -> bool {
+        let dragging = !matches!(self.drag, Drag::Idle | Drag::Pressed { .. });
+        self.drag = Drag::Idle;
+        dragging
+    }
+
+    pub fn guide(&self, layout: &HeaderLayout) -> Option<Guide> {
+        match self.drag {
+            Drag::Resizing { .. } => Some(Guide::Resize(self.guide_x)),
+            Drag::Moving { slot, .. } => layout.edges.get(slot).map(|&x| Guide::Drop(x)),
+            _ => None,
+        }
+    }
+
+    /// CSS cursor for the pointer position.
+    pub fn cursor(&self, layout: &HeaderLayout, x: f64, y: f64) -> &'static str {
+        match self.drag {
+            Drag::Resizing { .. } => "col-resize",
+            Drag::Moving { .. } => "grabbing",
+            _ => match layout.hit(x, y) {
+                Some(HeaderHit::Border(_)) => "col-resize",
+                Some(HeaderHit::Cell(_)) => "grab",
+                None => "default",
+            },
+        }
+    }
+}
+
+/// Position for `Schema::move_col` that puts `id` in front of the visible column
+/// at `slot`, or after the last visible one. Hidden columns keep their neighbours.
+pub fn drop_position(schema: &Schema, id: &str, slot: usize) -> Option<usize> {
+    let others: Vec<&str> = schema
+        .get_display_cols()
+        .map(|c| c.id.as_str())
+        .filter(|&c| c != id)
+        .collect();
+    let visible: Vec<&str> = schema.get_visible_cols().map(|c| c.id.as_str()).collect();
+    let from = visible.iter().position(|&c| c == id)?;
+    let visible: Vec<&str> = visible.into_iter().filter(|&c| c != id).collect();
+    let slot = if slot > from { slot - 1 } else { slot };
+    Some(match visible.get(slot) {
+        Some(before) => others.iter().position(|c| c == before)?,
+        None => match visible.last() {
+            Some(last) => others.iter().position(|c| c == last)? + 1,
+            None => 0,
+        },
+    })
+}
@@ -0,0 +1,190 @@
+use crate::grid::decimal::*;
+use serde::{Deserialize, Serialize};
+
+/// Price display for futures, bonds and FX ladders, set as `Column.price`.
+/// Rounds to the tick size, optionally renders fractions (`99-16+` for 32nds)
+/// and splits off the "big figure" digits so they can be drawn emphasized.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct PriceFormat {
+    #[serde(default)]
+    pub tick_size: f64, // 0 - no rounding, ignored for fractions
+    #[serde(default)]
+    pub fraction: u32, // 0 - decimal; 2..256 - denominator, 32 renders 99-16
+    #[serde(default)]
+    pub sub_fraction: u32, // splits one fraction tick: 2 "+", 4 "2 + 6", 8 "0..7"
+    #[serde(default)]
+    pub big_figure_digits: usize, // emphasized digits, 0 - no emphasis
+    #[serde(default)]
+    pub trailing_digits: usize, // digits after the big figure, e.g. 1 for the FX pipette
+}
+
+/// Formatted price split for rendering: `lead` + emphasized `big_figure` + `tail`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PriceParts {
+    pub lead: String,
+    pub big_figure: String,
+    pub tail: String,
+}
+
+impl PriceParts {
+    pub fn to_text(&self) -> String {
+        [self.lead.as_str(), &self.big_figure, &self.tail].concat()
+    }
+}
+
+const FRACTIONS: [u32; 9] = [0, 2, 4, 8, 16, 32, 64, 128, 256];
+const SUB_FRACTIONS: [u32; 4] = [0, 2, 4, 8];
+
+impl PriceFormat {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.tick_size >= 0.0 && self.tick_size.is_finite()) {
+            return Err(format!("invalid tick size {}", self.tick_size));
+        }
+        if !FRACTIONS.contains(&self.fraction) {
+            return Err(format!(
+                "fraction must be a power of two up to 256, got {}",
+                self.fraction
+            ));
+        }
+        if !SUB_FRACTIONS.contains(&self.sub_fraction) {
+            return Err(format!(
+                "sub fraction must be 2, 4 or 8, got {}",
+                self.sub_fraction
+            ));
+        }
+        if self.sub_fraction > 0 && self.fraction == 0 {
+            return Err("sub fraction requires a fraction".to_string());
+        }
+        Ok(())
+    }
+
+    /// Number of fraction digits needed to show a tick, e.g. 0.0005 => 4.
+    pub fn tick_precision(&self) -> usize {
+        if self.tick_size <= 0.0 {
+            return 0;
+        }
+        let s = format!("{}", self.tick_size);
+        s.find('.').map_or(0, |p| s.len() - p - 1)
+    }
+
+    pub fn round_to_tick(&self, v: f64) -> f64 {
+        let tick = if self.fraction > 0 {
+            1.0 / (self.fraction * std::cmp::max(1, self.sub_fraction)) as f64
+        } else {
+            self.tick_size
+        };
+        if tick > 0.0 {
+            (v / tick).round() * tick
+        } else {
+            v
+        }
+    }
+
+    pub fn format(&self, v: f64, precision: usize) -> String {
+        self.format_parts(v, precision).to_text()
+    }
+
+    pub fn format_parts(&self, v: f64, precision: usize) -> PriceParts {
+        let v = self.round_to_tick(v);
+        let text = if self.fraction > 0 {
+            self.format_fraction(v)
+        } else {
+            format!("{:.*}", std::cmp::max(precision, self.tick_precision()), v)
+        };
+        self.split(text)
+    }
+
+    /// Same as `format_parts` without going through f64.
+    pub fn format_decimal_parts(&self, v: Decimal, precision: usize) -> PriceParts {
+        let text = if self.fraction > 0 {
+            let units = round_div(
+                (v.value as i128).abs() * self.units_per_int() as i128,
+                pow10(v.scale),
+            );
+            self.fraction_text(v.value < 0, units as u64)
+        } else {
+            let tick_precision = std::cmp::min(self.tick_precision(), MAX_SCALE as usize);
+            let v = if self.tick_size > 0.0 {
+                let tick = (self.tick_size * pow10(tick_precision as u32) as f64).round();
+                v.round_to(Decimal::new(tick as i64, tick_precision as u32))
+            } else {
+                v
+            };
+            v.format(std::cmp::max(precision, tick_precision))
+        };
+        self.split(text)
+    }
+
+    pub fn format_decimal(&self, v: Decimal, precision: usize) -> String {
+        self.format_decimal_parts(v, precision).to_text()
+    }
+
+    fn units_per_int(&self) -> u64 {
+        (self.fraction * std::cmp::max(1, self.sub_fraction)) as u64
+    }
+
+    fn format_fraction(&self, v: f64) -> String {
+        let units = (v.abs() * self.units_per_int() as f64).round() as u64;
+        self.fraction_text(v < 0.0, units)
+    }
+
+    /// `units` of 1/(fraction * sub_fraction), e.g. 99-16+ for 3185 64ths with 32nds and halves.
+    fn fraction_text(&self, negative: bool, units: u64) -> String {
+        let sub = std::cmp::max(1, self.sub_fraction);
+        let per_int = self.units_per_int();
+        let int_part = units / per_int;
+        let rem = units % per_int;
+        let (ticks, sub_ticks) = (rem / sub as u64, rem % sub as u64);
+
+        let width = (self.fraction - 1).to_string().len();
+        let sub_char = match self.sub_fraction {
+            2 => ["", "+"][sub_ticks as usize].to_string(),
+            4 => ["0", "2", "+", "6"][sub_ticks as usize].to_string(),
+            8 => sub_ticks.to_string(),
+            _ => String::new(),
+        };
+        let sign = if negative && units > 0 { "-" } else { "" };
+        format!(
+            "{}{}-{:0width$}{}",
+            sign,
+            int_part,
+            ticks,
+            sub_char,
+            width = width
+        )
+    }
+
+    /// Splits the text counting digits from the end, separators don't count.
+    fn split(&self, text: String) -> PriceParts {
+        if self.big_figure_digits == 0 {
+            return PriceParts {
+                lead: text,
+                ..Default::default()
+            };
+        }
+        let tail_start = digit_boundary(&text, self.trailing_digits);
+        let big_start = digit_boundary(&text, self.trailing_digits + self.big_figure_digits);
+        PriceParts {
+            lead: text[..big_start].to_string(),
+            big_figure: text[big_start..tail_start].to_string(),
+            tail: text[tail_start..].to_string(),
+        }
+    }
+}
+
+/// Byte index where the last `digits` digits (and anything after them) begin.
+fn digit_boundary(text: &str, digits: usize) -> usize {
+    if digits == 0 {
+        return text.len();
+    }
+    let mut count = 0;
+    for (i, c) in text.char_indices().rev() {
+        if c.is_ascii_digit() || c == '+' {
+            count += 1;
+            if count == digits {
+                return i;
+            }
+        }
+    }
+    0
+}
@@ -1,18 +1,27 @@
 #![allow(dead_code)]
 use super::ctx2d::*;
 use super::ds::*;
+use crate::grid::autofit::*;
 use crate::grid::column::*;
+use crate::grid::header::*;
+use crate::grid::measure::*;
+use crate::grid::pointer::*;
+use crate::grid::price::*;
 use crate::grid::schema::*;
+use crate::grid::selection::*;
 use crate::grid::sparkline::*;
+use crate::grid::theme::*;
 
 use js_sys::Date;
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::f64;
 use wasm_bindgen::prelude::*;
 use web_sys::CanvasRenderingContext2d;
 
-pub const HEADER_LINES: usize = 1;
 const HIGHLIGHT_DURATION: i64 = 100;
-const ROW_HEIGHT: usize = 40;
+const MIN_COL_WIDTH: f64 = 100.0;
+const ROW_PADDING_V: f64 = 12.0; // above and below the text when the row height comes from the fonts
 const MARGIN: u32 = 0;
 
 #[wasm_bindgen]
@@ -31,6 +40,10 @@ pub const fn num_size() -> usize {
     std::mem::size_of::<f64>()
 }
 
+pub const fn symbol_size() -> usize {
+    std::mem::size_of::<u32>()
+}
+
 pub fn is_highlighted(time: f64) -> bool {
     let now = Date::new_0().get_time() as i64;
     now - time as i64 <= HIGHLIGHT_DURATION
@@ -46,8 +59,13 @@ pub struct GridRenderer<'a> {
     top: u32,
     width: u32,
     height: u32,
-    col_width: f64, // todo: use col widths later
+    col_x: Vec<f64>, // left edge of each visible column and the right edge of the last, from left()
     top_index: usize,
+    format: FormatContext<'a>,
+    theme: Option<&'a Theme>, // None - dark
+    auto_row_height: bool,    // row height follows the theme fonts
+    measure_cache: Option<&'a RefCell<MeasureCache>>,
+    selection: Option<&'a ResolvedSelection>,
 }
 
 // impl<'a> Default for GridRenderer<'a> {
@@ -67,13 +85,8 @@ impl<'a> GridRenderer<'a> {
         top: u32,
         width: u32,
         height: u32,
-        row_height: usize,
+        row_height: usize, // 0 - from the theme fonts, measured by `with_theme`
     ) -> GridRenderer<'a> {
-        let row_height = if row_height == 0 {
-            ROW_HEIGHT
-        } else {
-            row_height
-        };
         GridRenderer {
             ctx: Some(ctx),
             schema: Some(schema),
@@ -83,10 +96,58 @@ impl<'a> GridRenderer<'a> {
             height,
             row_height,
             margin: MARGIN,
+            auto_row_height: row_height == 0,
             ..Default::default()
         }
     }
 
+    /// Row height for the body and header fonts when it was not given explicitly.
+    fn fit_row_height(&mut self) {
+        if self.auto_row_height {
+            let ctx = self.get_ctx();
+            let theme = self.theme();
+            let text_height = [&theme.font, &theme.header_font, &theme.numeric_font]
+                .iter()
+                .map(|font| font_height(ctx, font))
+                .fold(0.0, f64::max);
+            self.row_height = (text_height + 2.0 * ROW_PADDING_V).ceil() as usize;
+        }
+    }
+
+    /// Locale and clock for cell values, en-US and the system clock by default.
+    pub fn with_format(mut self, format: FormatContext<'a>) -> GridRenderer<'a> {
+        self.format = format;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: &'a Theme) -> GridRenderer<'a> {
+        self.theme = Some(theme);
+        self.fit_row_height();
+        self
+    }
+
+    /// Text widths are measured once per string and font while the cache lives.
+    pub fn with_measure_cache(mut self, cache: &'a RefCell<MeasureCache>) -> GridRenderer<'a> {
+        self.measure_cache = Some(cache);
+        self
+    }
+
+    /// Selected cells and cursor to paint, resolved against the data being rendered.
+    pub fn with_selection(mut self, selection: &'a ResolvedSelection) -> GridRenderer<'a> {
+        self.selection = Some(selection);
+        self
+    }
+
+    /// Whole data rows that fit below the header, the cursor is kept within them.
+    pub fn page_size(&self) -> usize {
+        ((self.client_height() / self.row_height.max(1) as f64) as usize)
+            .saturating_sub(self.header_lines())
+    }
+
+    pub fn theme(&self) -> &Theme {
+        self.theme.unwrap_or_else(|| Theme::dark_ref())
+    }
+
     pub fn clear(&self) {
         let ctx = self.get_ctx();
         fill_rect(
@@ -95,7 +156,7 @@ impl<'a> GridRenderer<'a> {
             self.top as f64,
             self.width as f64,
             self.height as f64,
-            &"#0b0e17",
+            &self.theme().background,
         );
 
         fill_rect(
@@ -104,11 +165,11 @@ impl<'a> GridRenderer<'a> {
             self.top(),
             self.client_width(),
             self.client_height(),
-            &"#101722",
+            &self.theme().client_background,
         );
 
-        // red: #ff3b69
-        set_fill_style(ctx, "#03c67a");
+        set_fill_style(ctx, &self.theme().text);
+        set_font(ctx, &self.theme().font);
         set_text_baseline(ctx, "middle");
     }
 
@@ -117,7 +178,9 @@ impl<'a> GridRenderer<'a> {
         self.calc_col_width();
         self.render_gridlines(ds);
         self.clip_begin();
+        self.render_selection(ds);
         self.render_data(ds);
+        self.render_focus(ds);
         self.render_header();
         self.clip_end();
     }
@@ -127,26 +190,39 @@ impl<'a> GridRenderer<'a> {
         self.clear();
 
         ctx.begin_path();
-        ctx.set_stroke_style(&"#232832".into());
+        ctx.set_stroke_style_str(&self.theme().gridline);
+
+        // Header cell borders, group cells span several columns.
+        let header_bottom = self.get_y(self.header_lines());
+        horizontal_line(ctx, self.left(), self.right(), self.top());
+        vertical_line(ctx, self.top(), header_bottom, self.get_x(0));
+        for cell in self.header_cells() {
+            let right = self.get_x(cell.last + 1);
+            let bottom = self.get_y(cell.line + cell.lines);
+            if right < self.right() {
+                vertical_line(ctx, self.get_y(cell.line), bottom, right);
+            }
+            horizontal_line(ctx, self.get_x(cell.first), right.min(self.right()), bottom);
+        }
 
-        // Vertical lines.
-        let mut col_index = 0;
-        let last_y = self.data_bottom(ds.row_count - self.top_index);
-        for _col in self.schema.unwrap().get_visible_cols() {
+        // Vertical lines, including the right edge of the last column.
+        let last_y = self.data_bottom(ds.row_count.saturating_sub(self.top_index));
+        for col_index in 0..self.col_x.len() {
             let x = self.get_x(col_index);
             if x < self.right() {
-                vertical_line(ctx, self.top(), last_y, x);
-                col_index += 1;
+                vertical_line(ctx, header_bottom, last_y, x);
             } else {
                 break;
             }
         }
 
         // Horizontal lines.
-        let mut row_index = 0;
+        let mut row_index = self.header_lines();
         loop {
             let y = self.get_y(row_index);
-            if y < self.bottom() && row_index <= ds.row_count - self.top_index + HEADER_LINES {
+            if y < self.bottom()
+                && row_index <= ds.row_count.saturating_sub(self.top_index) + self.header_lines()
+            {
                 horizontal_line(ctx, self.left(), self.right(), y);
                 row_index += 1;
             } else {
@@ -161,97 +237,308 @@ impl<'a> GridRenderer<'a> {
         ctx.stroke();
     }
 
+    /// Group cells then column names, see `header_cells`.
     pub fn render_header(&self) {
-        let mut index = 0;
-        for col in self.schema.unwrap().get_visible_cols() {
-            let x = self.get_x(index);
-            self.render_text(col.name.as_str(), x, 0_f64, "center", false);
-            index += 1;
+        let ctx = self.get_ctx();
+        ctx.save();
+        set_fill_style(ctx, &self.theme().header_text);
+        let font = &self.theme().header_font;
+        set_font(ctx, font);
+        set_text_align(ctx, "center");
+        for cell in self.header_cells() {
+            let x = self.get_x(cell.first);
+            let width = self.get_x(cell.last + 1) - x;
+            let y = self.get_y(cell.line);
+            let height = (cell.lines * self.row_height) as f64;
+            let text = fit_text(cell.text, width - PADDING_H, false, &mut |t: &str| {
+                self.text_width(t, font)
+            });
+            fill_text_aligned(ctx, &text, x, y, width, height, "center", None);
         }
+        ctx.restore();
+    }
+
+    fn header_cells(&self) -> Vec<HeaderCell<'_>> {
+        let cols: Vec<&Column> = self.schema.unwrap().get_visible_cols().collect();
+        header_cells(&cols, self.header_lines())
+    }
+
+    /// Group lines plus the column names.
+    pub fn header_lines(&self) -> usize {
+        self.schema.map_or(1, |schema| schema.header_lines.max(1))
     }
 
     pub fn render_data(&self, ds: &DataSource) {
         let ts_col = self.schema.unwrap().get_col_by_type(ColumnType::Timestamp);
+        let fonts: Vec<String> = self
+            .schema
+            .unwrap()
+            .get_visible_cols()
+            .map(|col| self.theme().cell_font(col, false))
+            .collect();
+        let ctx = self.get_ctx();
+        ctx.save();
 
         for row_index in 0_usize.. {
-            let y = self.get_y(row_index + HEADER_LINES);
+            let y = self.get_y(row_index + self.header_lines());
             let row = self.top_index + row_index;
 
             if y < self.bottom() && row < ds.row_count {
-                let highlight = if ts_col.is_some() {
-                    is_highlighted(ds.get_value_f64(row, ts_col.unwrap()).unwrap_or_default())
-                } else {
-                    false
+                let highlight = match ts_col {
+                    Some(ts_col) => is_highlighted(
+                        ds.get_value_f64(row, ts_col).unwrap_or_default() * 1000.0
+                            / ts_col.epoch_unit.per_second(),
+                    ),
+                    None => false,
                 };
 
-                let mut col_index = 0;
-                for col in self.schema.unwrap().get_visible_cols() {
-                    let x = self.get_x(col_index);
-                    self.render_cell(ds, row, x, y, col, highlight && col.highlight);
-                    col_index += 1;
+                for (col_index, col) in self.schema.unwrap().get_visible_cols().enumerate() {
+                    set_font(ctx, &fonts[col_index]);
+                    self.render_cell(
+                        ds,
+                        row,
+                        col_index,
+                        y,
+                        col,
+                        &fonts[col_index],
+                        highlight && col.highlight,
+                    );
                 }
             } else {
                 break;
             }
         }
+        ctx.restore();
     }
 
+    /// Background of the selected cells in view.
+    pub fn render_selection(&self, ds: &DataSource) {
+        let Some(selection) = self.selection.filter(|s| !s.is_empty()) else {
+            return;
+        };
+        let ctx = self.get_ctx();
+        ctx.save();
+        set_fill_style(ctx, &self.theme().selection_background);
+        let end = ds.row_count.min(self.top_index + self.visible_row_count());
+        for row in self.top_index.min(end)..end {
+            let y = self.get_y(row - self.top_index + self.header_lines());
+            for col_index in 0..self.col_x.len().saturating_sub(1) {
+                if selection.contains(row, col_index) {
+                    let (x, width) = (self.get_x(col_index), self.col_width(col_index));
+                    ctx.fill_rect(x + 0.5, y + 0.5, width - 1.0, self.row_height as f64 - 1.0);
+                }
+            }
+        }
+        ctx.restore();
+    }
+
+    /// Outline of the cursor cell when it is in view.
+    pub fn render_focus(&self, ds: &DataSource) {
+        let Some((row, col_index)) = self.selection.and_then(|s| s.cursor) else {
+            return;
+        };
+        if row < self.top_index
+            || row >= ds.row_count.min(self.top_index + self.visible_row_count())
+        {
+            return;
+        }
+        let ctx = self.get_ctx();
+        ctx.save();
+        ctx.set_stroke_style_str(&self.theme().focus);
+        ctx.set_line_width(2.0);
+        ctx.stroke_rect(
+            self.get_x(col_index) + 1.0,
+            self.get_y(row - self.top_index + self.header_lines()) + 1.0,
+            self.col_width(col_index) - 2.0,
+            self.row_height as f64 - 2.0,
+        );
+        ctx.restore();
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn render_cell(
         &self,
         ds: &DataSource,
         row: usize,
-        x: f64,
+        col_index: usize,
         y: f64,
         col: &Column,
+        font: &str,
         highlight: bool,
     ) {
         if col.col_type == ColumnType::Sparkline {
-            self.render_sparkline(ds, row, x, y, col);
+            self.render_sparkline(ds, row, col_index, y, col);
             return;
         }
 
-        let v = match col.col_type {
-            ColumnType::String => ds.get_value_str(row, col),
-            _ => col.format_value(ds.get_value_f64(row, col)),
-        };
+        if col.price.is_some() && col.is_numeric() {
+            let parts = match col.col_type {
+                ColumnType::Decimal => col
+                    .format_decimal_price_parts_with(ds.get_value_decimal(row, col), &self.format),
+                _ => col.format_price_parts_with(ds.get_value_f64(row, col), &self.format),
+            };
+            if let Some(parts) = parts {
+                self.render_price(&parts, col_index, y, col, font, highlight);
+                return;
+            }
+        }
+
+        let v = ds.format_cell(row, col, &self.format, &self.schema.unwrap().null_text);
 
-        if v.is_some() {
+        if let Some(v) = v {
             let align = match col.align.as_str() {
                 "" => match col.col_type {
-                    ColumnType::String => "left",
+                    ColumnType::String | ColumnType::Symbol | ColumnType::VarString => "left",
                     ColumnType::Date | ColumnType::DateTime | ColumnType::Timestamp => "center",
                     _ => "right",
                 },
                 _ => col.align.as_str(),
             };
-            self.render_text(&v.unwrap(), x, y, align, highlight);
+            if col.overflow == Overflow::Clip && !col.shows_number() {
+                let ctx = self.get_ctx();
+                clip_begin(
+                    ctx,
+                    self.get_x(col_index),
+                    y,
+                    self.col_width(col_index),
+                    self.row_height as f64,
+                );
+                self.render_text(&v, col_index, y, align, highlight);
+                clip_end(ctx);
+            } else {
+                let v = self.fit_text(&v, font, col.shows_number(), col_index);
+                self.render_text(&v, col_index, y, align, highlight);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_price(
+        &self,
+        parts: &PriceParts,
+        col_index: usize,
+        y: f64,
+        col: &Column,
+        font: &str,
+        highlight: bool,
+    ) {
+        let big_font = self.theme().cell_font(col, true);
+        let align = if col.align.is_empty() {
+            "right"
+        } else {
+            col.align.as_str()
+        };
+        let width = self.text_width(&parts.lead, font)
+            + self.text_width(&parts.big_figure, &big_font)
+            + self.text_width(&parts.tail, font);
+        if width > self.text_max_width(col_index) {
+            let overflow = self.fit_text(NUMERIC_OVERFLOW, font, true, col_index);
+            self.render_text(&overflow, col_index, y, align, highlight);
+            return;
+        }
+        fill_text_runs(
+            self.get_ctx(),
+            &[
+                (&parts.lead, None),
+                (&parts.big_figure, Some(&big_font)),
+                (&parts.tail, None),
+            ],
+            self.get_x(col_index),
+            y,
+            self.col_width(col_index),
+            self.row_height as f64,
+            align,
+            self.highlight_color(highlight),
+        );
+    }
+
+    /// Room for text in a cell, the text is drawn `PADDING_H` from the border.
+    fn text_max_width(&self, col_index: usize) -> f64 {
+        self.col_width(col_index) - PADDING_H
+    }
+
+    /// Width in `font`; measuring switches the canvas font and restores it.
+    pub fn text_width(&self, text: &str, font: &str) -> f64 {
+        let ctx = self.get_ctx();
+        let measure = |text: &str| {
+            ctx.save();
+            set_font(ctx, font);
+            let width = measure_text(ctx, text).0;
+            ctx.restore();
+            width
+        };
+        match self.measure_cache {
+            Some(cache) => cache.borrow_mut().measure(font, text, measure),
+            None => measure(text),
+        }
+    }
+
+    fn fit_text<'t>(
+        &self,
+        text: &'t str,
+        font: &str,
+        numeric: bool,
+        col_index: usize,
+    ) -> Cow<'t, str> {
+        fit_text(
+            text,
+            self.text_max_width(col_index),
+            numeric,
+            &mut |t: &str| self.text_width(t, font),
+        )
+    }
+
+    /// Widths fitting the header and cell text of the rows picked by `mode`, see `fit_widths`.
+    pub fn fit_col_widths(&self, ds: &DataSource, mode: FitMode) -> Vec<Option<f64>> {
+        let rows = fit_rows(mode, ds.row_count, self.top_index, self.visible_row_count());
+        fit_widths(
+            self.schema.unwrap(),
+            ds,
+            &rows,
+            &self.format,
+            self.theme(),
+            &mut |text: &str, font: &str| self.text_width(text, font),
+        )
+    }
+
+    fn highlight_color(&self, highlight: bool) -> Option<&str> {
+        if highlight {
+            Some(&self.theme().highlight_text)
+        } else {
+            None
         }
     }
 
-    fn render_text(&self, text: &str, x: f64, y: f64, align: &str, highlight: bool) {
+    fn render_text(&self, text: &str, col_index: usize, y: f64, align: &str, highlight: bool) {
         fill_text_aligned(
             self.get_ctx(),
             text,
-            x,
+            self.get_x(col_index),
             y,
-            self.col_width(),
+            self.col_width(col_index),
             self.row_height as f64,
             align,
-            highlight,
+            self.highlight_color(highlight),
         );
     }
 
-    fn render_sparkline(&self, ds: &DataSource, row: usize, x: f64, y: f64, col: &Column) {
-        let data = ds.get_sparkline(row, col);
-        if data.is_some() {
-            let mut ss = Sparkline::new();
+    fn render_sparkline(
+        &self,
+        ds: &DataSource,
+        row: usize,
+        col_index: usize,
+        y: f64,
+        col: &Column,
+    ) {
+        if let Some(data) = ds.get_sparkline(row, col) {
+            let mut ss = Sparkline::new().with_theme(self.theme());
             ss.render(
                 self.get_ctx(),
-                x,
+                self.get_x(col_index),
                 y,
-                self.col_width(),
+                self.col_width(col_index),
                 self.row_height as f64,
-                &data.unwrap()[..],
+                &data[..],
             );
         }
     }
@@ -261,11 +548,56 @@ impl<'a> GridRenderer<'a> {
         let now = Date::new_0().get_time() as i64;
         if now - time as i64 <= HIGHLIGHT_DURATION {
             ctx.save();
-            fill_rect(ctx, x, y, width, self.row_height as f64, "#ffffff22");
+            fill_rect(
+                ctx,
+                x,
+                y,
+                width,
+                self.row_height as f64,
+                &self.theme().highlight_background,
+            );
             ctx.restore();
         }
     }
 
+    /// Header geometry for hit testing pointer events, valid after `render`.
+    pub fn header_layout(&self) -> HeaderLayout {
+        HeaderLayout {
+            top: self.top(),
+            bottom: self.get_y(self.header_lines()),
+            edges: (0..self.col_x.len()).map(|i| self.get_x(i)).collect(),
+            ids: self
+                .schema
+                .unwrap()
+                .get_visible_cols()
+                .map(|col| col.id.to_string())
+                .collect(),
+            row_height: self.row_height as f64,
+        }
+    }
+
+    /// Resize guide or drop indicator of a header drag in progress.
+    pub fn render_guide(&self, guide: Guide) {
+        let ctx = self.get_ctx();
+        ctx.save();
+        ctx.begin_path();
+        ctx.set_stroke_style_str(&self.theme().highlight_text);
+        match guide {
+            Guide::Resize(x) => vertical_line(ctx, self.top(), self.bottom(), x.floor() + 0.5),
+            Guide::Drop(x) => {
+                ctx.set_line_width(3.0);
+                vertical_line(
+                    ctx,
+                    self.top(),
+                    self.get_y(self.header_lines()),
+                    x.floor() + 0.5,
+                );
+            }
+        }
+        ctx.stroke();
+        ctx.restore();
+    }
+
     pub fn clip_begin(&self) {
         clip_begin(
             self.get_ctx(),
@@ -290,14 +622,37 @@ impl<'a> GridRenderer<'a> {
         self.schema.unwrap().visible_col_count
     }
 
+    /// Columns with a `width` keep it, the rest share the remaining space evenly.
     pub fn calc_col_width(&mut self) {
-        let visible_count = self.schema.unwrap().get_visible_row_count();
-        assert!(visible_count > 0);
+        let schema = self.schema.unwrap();
+        let fixed: f64 = schema
+            .get_visible_cols()
+            .filter(|c| c.width > 0.0)
+            .map(|c| c.width)
+            .sum();
+        let auto_count = schema.get_visible_cols().filter(|c| c.width <= 0.0).count();
         // laziness: enforce min column width until we support horizontal scroll
-        self.col_width = std::cmp::max(100, self.client_width() as usize / visible_count) as f64;
+        let auto_width = if auto_count > 0 {
+            MIN_COL_WIDTH.max(((self.client_width() - fixed).max(0.0) / auto_count as f64).floor())
+        } else {
+            0.0
+        };
+        self.col_x = std::iter::once(0.0)
+            .chain(schema.get_visible_cols().scan(0.0, |x, col| {
+                *x += if col.width > 0.0 {
+                    col.width
+                } else {
+                    auto_width
+                };
+                Some(*x)
+            }))
+            .collect();
     }
-    pub fn col_width(&self) -> f64 {
-        self.col_width
+    pub fn col_width(&self, col_index: usize) -> f64 {
+        match (self.col_x.get(col_index), self.col_x.get(col_index + 1)) {
+            (Some(left), Some(right)) => right.floor() - left.floor(),
+            _ => 0.0,
+        }
     }
     pub fn client_width(&self) -> f64 {
         (self.width - 2 * self.margin) as f64
@@ -320,16 +675,25 @@ impl<'a> GridRenderer<'a> {
     pub fn data_bottom(&self, row_count: usize) -> f64 {
         std::cmp::min(
             self.bottom() as usize,
-            self.top as usize + (row_count + HEADER_LINES) * self.row_height,
+            self.top as usize + (row_count + self.header_lines()) * self.row_height,
         ) as f64
             - 0.5
     }
+    /// Data rows drawn below the header, the last one may be cut off.
+    pub fn visible_row_count(&self) -> usize {
+        ((self.client_height() / self.row_height.max(1) as f64).ceil() as usize)
+            .saturating_sub(self.header_lines())
+    }
     pub fn mid(&self) -> f64 {
         self.left() + ((self.client_width() / 2.0).round())
     }
 
     pub fn get_x(&self, col_index: usize) -> f64 {
-        self.left() + (col_index as f64 * self.col_width()).floor()
+        let x = match self.col_x.get(col_index) {
+            Some(x) => *x,
+            None => self.col_x.last().copied().unwrap_or_default(),
+        };
+        self.left() + x.floor()
     }
 
     pub fn get_y(&self, row_index: usize) -> f64 {
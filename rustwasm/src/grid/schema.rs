@@ -1,7 +1,17 @@
 #![allow(dead_code)]
+use crate::error::*;
 use crate::grid::column::*;
+use crate::grid::datefmt::*;
+use crate::grid::decimal::*;
+use crate::grid::header::*;
+use crate::grid::layout::*;
+use crate::grid::numfmt::*;
 use crate::grid::renderer::*;
+use crate::grid::validate::is_date_type;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const CUM_SIZE_COL_ID: &str = "cumSize"; // don't change it - used by the UI demo
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct Schema {
@@ -9,7 +19,25 @@ pub struct Schema {
     #[serde(default)]
     pub col_width: f64, // todo: switch to col-based widths
     #[serde(default)]
+    pub key: String, // id of the column identifying a row, e.g. an order id; empty - buffer index
+    #[serde(default)]
+    pub order: Vec<usize>, // display order as indices into `cols`, empty - schema order
+    #[serde(default)]
     pub visible_col_count: usize,
+    #[serde(default)]
+    pub header_lines: usize, // group lines plus the column names, computed
+    #[serde(default)]
+    pub null_mode: NullMode,
+    #[serde(default)]
+    pub null_text: String, // placeholder rendered for null cells
+    #[serde(default)]
+    pub validity_size: usize, // bytes reserved for the validity bitmap, computed
+    #[serde(default)]
+    pub buffer_header: bool, // buffers start with a layout header, see layout.rs
+    #[serde(default)]
+    pub data_width: usize, // row width in bytes, computed
+    #[serde(default)]
+    pub layout_hash: u32, // schema fingerprint, computed
 }
 
 impl Schema {
@@ -31,27 +59,210 @@ impl Schema {
     }
 
     pub fn get_visible_cols(&self) -> impl Iterator<Item = &Column> {
-        self.cols.iter().filter(|&o| !o.hidden).into_iter()
+        self.get_display_cols().filter(|&o| !o.hidden)
+    }
+
+    /// All columns, hidden included, in display order.
+    pub fn get_display_cols(&self) -> impl Iterator<Item = &Column> {
+        (0..self.cols.len())
+            .filter_map(move |i| self.cols.get(self.order.get(i).copied().unwrap_or(i)))
+    }
+
+    pub fn get_key_col(&self) -> Option<&Column> {
+        self.get_col_by_id(&self.key)
+    }
+
+    pub fn get_col_index(&self, id: &str) -> Option<usize> {
+        self.cols.iter().position(|o| o.id == id)
+    }
+
+    /// Position of a column in display order, hidden columns included.
+    pub fn get_display_index(&self, id: &str) -> Option<usize> {
+        self.get_display_cols().position(|o| o.id == id)
+    }
+
+    pub fn set_col_hidden(&mut self, id: &str, hidden: bool) -> GridResult<()> {
+        self.update_col(id, |col| col.hidden = hidden)
+    }
+
+    /// Moves a column to display position `to`, clamped to the last one.
+    /// The data layout is unchanged so existing buffers stay valid.
+    pub fn move_col(&mut self, id: &str, to: usize) -> GridResult<()> {
+        let from = self
+            .get_display_index(id)
+            .ok_or_else(|| GridError::missing_column(id))?;
+        let mut order: Vec<usize> = (0..self.cols.len())
+            .map(|i| self.order.get(i).copied().unwrap_or(i))
+            .collect();
+        let index = order.remove(from);
+        order.insert(to.min(order.len()), index);
+        self.order = order;
+        Ok(())
+    }
+
+    /// Fixed width in pixels, 0 shares the space left by the other columns.
+    pub fn set_col_width(&mut self, id: &str, width: f64) -> GridResult<()> {
+        if !width.is_finite() || width < 0.0 {
+            return Err(GridError::new(
+                ErrorKind::InvalidArgument,
+                format!("invalid width {}, expected 0 or more pixels", width),
+            )
+            .with_column(id));
+        }
+        // called on every resize move, a width needs no normalizing
+        let index = self
+            .get_col_index(id)
+            .ok_or_else(|| GridError::missing_column(id))?;
+        self.cols[index].width = width;
+        Ok(())
+    }
+
+    /// Replaces the format string, validated the same way as in `normalize_schema`.
+    pub fn set_col_format(&mut self, id: &str, format: &str) -> GridResult<()> {
+        self.update_col(id, |col| col.format = format.to_string())
+    }
+
+    /// Applies `f` to a copy of the column and keeps it only if it still normalizes.
+    /// The data layout can't change here, so only the column and the visible
+    /// count and header lines are recomputed.
+    fn update_col(&mut self, id: &str, f: impl FnOnce(&mut Column)) -> GridResult<()> {
+        let index = self
+            .get_col_index(id)
+            .ok_or_else(|| GridError::missing_column(id))?;
+        let mut col = self.cols[index].clone();
+        f(&mut col);
+        let offset = col.data_offset;
+        normalize_col(index, &mut col, offset)?;
+        self.cols[index] = col;
+        self.visible_col_count = self.cols.iter().filter(|c| !c.hidden).count();
+        self.header_lines = header_lines(self.get_visible_cols());
+        Ok(())
     }
 }
 
-pub fn normalize_schema(schema: &mut Schema) {
-    let mut offset = 0_usize;
-    for col in &mut schema.cols {
-        assert!(col.id != "");
-        assert!(
-            col.col_type == ColumnType::String
-                || col.col_type == ColumnType::Sparkline
-                || col.size == num_size()
-        );
+pub fn validity_size(col_count: usize) -> usize {
+    col_count.div_ceil(8)
+}
+
+pub fn normalize_schema(schema: &mut Schema) -> GridResult<()> {
+    schema.visible_col_count = 0;
+    schema.validity_size = match schema.null_mode {
+        NullMode::Bitmap => validity_size(schema.cols.len()),
+        _ => 0,
+    };
+    if schema.order.is_empty() {
+        schema.order = (0..schema.cols.len()).collect();
+    }
+    let mut seen = vec![false; schema.cols.len()];
+    for &index in &schema.order {
+        if index >= seen.len() || std::mem::replace(&mut seen[index], true) {
+            return Err(GridError::schema(format!(
+                "order must list every column index once, got {:?}",
+                schema.order
+            )));
+        }
+    }
+    if schema.order.len() != schema.cols.len() {
+        return Err(GridError::schema(format!(
+            "order has {} entries for {} columns",
+            schema.order.len(),
+            schema.cols.len()
+        )));
+    }
+    let mut offset = schema.validity_size;
+    let mut ids = HashMap::new();
+    for (index, col) in schema.cols.iter_mut().enumerate() {
+        normalize_col(index, col, offset)?;
+        if let Some(first) = ids.insert(col.id.clone(), index) {
+            return Err(GridError::schema(format!(
+                "duplicate column id '{}', first used by column #{}",
+                col.id, first
+            ))
+            .with_column(&col.id)
+            .with_offset(offset));
+        }
         col.data_offset = offset;
-        if col.col_type == ColumnType::String || col.col_type == ColumnType::Sparkline {
-            offset += col.size;
-        } else {
-            offset += num_size();
+        col.null_mode = schema.null_mode;
+        col.validity_index = index;
+        match col.col_type {
+            ColumnType::String | ColumnType::Sparkline => offset += col.size,
+            ColumnType::Symbol => {
+                col.size = symbol_size();
+                offset += symbol_size();
+            }
+            _ => offset += num_size(),
         }
         if !col.hidden {
             schema.visible_col_count += 1;
         }
     }
+    if !schema.key.is_empty() && schema.get_col_by_id(&schema.key).is_none() {
+        return Err(
+            GridError::schema(format!("key column '{}' not found", schema.key))
+                .with_column(&schema.key),
+        );
+    }
+    schema.data_width = offset;
+    schema.header_lines = header_lines(schema.get_visible_cols());
+    schema.layout_hash = schema_hash(schema);
+    Ok(())
+}
+
+/// Checks a single column and parses its format and zone, `offset` is only for errors.
+fn normalize_col(index: usize, col: &mut Column, offset: usize) -> GridResult<()> {
+    if col.id.is_empty() {
+        return Err(
+            GridError::schema(format!("column #{} has an empty id", index)).with_offset(offset),
+        );
+    }
+    if col.scale > MAX_SCALE {
+        return Err(GridError::schema(format!(
+            "scale {} exceeds the maximum of {}",
+            col.scale, MAX_SCALE
+        ))
+        .with_column(&col.id)
+        .with_offset(offset));
+    }
+    let sized = matches!(
+        col.col_type,
+        ColumnType::String | ColumnType::Sparkline | ColumnType::Symbol
+    );
+    if !sized && col.size != num_size() {
+        return Err(GridError::schema(format!(
+            "numeric column size must be {}, got {}",
+            num_size(),
+            col.size
+        ))
+        .with_column(&col.id)
+        .with_offset(offset));
+    }
+    if col.is_numeric() {
+        col.number_format = Some(NumberFormat::parse(&col.format).map_err(|e| {
+            GridError::schema(e)
+                .with_column(&col.id)
+                .with_offset(offset)
+        })?);
+    }
+    if is_date_type(col.col_type) {
+        col.zone = Some(Zone::parse(&col.timezone).map_err(|e| {
+            GridError::schema(e)
+                .with_column(&col.id)
+                .with_offset(offset)
+        })?);
+    }
+    if col.col_type == ColumnType::Duration {
+        DurationStyle::parse(&col.format).map_err(|e| {
+            GridError::schema(e)
+                .with_column(&col.id)
+                .with_offset(offset)
+        })?;
+    }
+    if let Some(price) = &col.price {
+        price.validate().map_err(|e| {
+            GridError::schema(e)
+                .with_column(&col.id)
+                .with_offset(offset)
+        })?;
+    }
+    Ok(())
 }
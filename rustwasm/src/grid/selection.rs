@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// Identifies a row across data updates: the value of the schema `key` column,
+/// or the buffer index when the schema has none.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RowKey {
+    Number(f64),
+    Text(String),
+}
+
+impl Eq for RowKey {}
+
+// agrees with the derived `PartialEq`: 0.0 and -0.0 are one key, NaN matches nothing
+impl Hash for RowKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            RowKey::Number(n) if *n == 0.0 => 0u64.hash(state),
+            RowKey::Number(n) => n.to_bits().hash(state),
+            RowKey::Text(s) => s.hash(state),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CellRef {
+    pub row: RowKey,
+    pub col: String, // column id
+}
+
+/// Cursor cell, the range from `anchor` to the cursor, and whole rows added with ctrl.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Selection {
+    pub cursor: Option<CellRef>,
+    pub anchor: Option<CellRef>,
+    pub rows: Vec<RowKey>,
+}
+
+/// Cursor movement from the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,  // first column
+    End,   // last column
+    First, // first row, ctrl+Home
+    Last,  // last row, ctrl+End
+}
+
+impl Nav {
+    /// From a DOM `KeyboardEvent.key`.
+    pub fn parse(key: &str, ctrl: bool) -> Option<Nav> {
+        Some(match key {
+            "ArrowUp" => Nav::Up,
+            "ArrowDown" => Nav::Down,
+            "ArrowLeft" => Nav::Left,
+            "ArrowRight" => Nav::Right,
+            "PageUp" => Nav::PageUp,
+            "PageDown" => Nav::PageDown,
+            "Home" if ctrl => Nav::First,
+            "End" if ctrl => Nav::Last,
+            "Home" => Nav::Home,
+            "End" => Nav::End,
+            _ => return None,
+        })
+    }
+}
+
+/// Selection mapped to buffer rows and visible columns of the current data.
+/// Parts whose row or column is gone are dropped.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvedSelection {
+    pub cursor: Option<(usize, usize)>,
+    pub range: Option<((usize, usize), (usize, usize))>, // inclusive rows, inclusive columns
+    pub rows: Vec<usize>,                                // sorted
+}
+
+impl ResolvedSelection {
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        matches!(self.range, Some(((r0, r1), (c0, c1))) if (r0..=r1).contains(&row) && (c0..=c1).contains(&col))
+            || self.rows.binary_search(&row).is_ok()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cursor.is_none() && self.range.is_none() && self.rows.is_empty()
+    }
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.cursor.is_none() && self.rows.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Selection::default();
+    }
+
+    /// Click on a cell: shift extends the range from the anchor, ctrl toggles the whole row.
+    pub fn click(&mut self, cell: CellRef, shift: bool, ctrl: bool) {
+        if ctrl {
+            match self.rows.iter().position(|row| *row == cell.row) {
+                Some(index) => {
+                    self.rows.remove(index);
+                }
+                None => self.rows.push(cell.row.clone()),
+            }
+        } else if !shift {
+            self.rows.clear();
+        }
+        if !shift || self.anchor.is_none() {
+            self.anchor = Some(cell.clone());
+        }
+        self.cursor = Some(cell);
+    }
+
+    /// Scans `rows` once, e.g. the visible window, or all rows to find every selected one.
+    /// A cursor or anchor outside `rows` is looked for at its row in `last`, a previous
+    /// result. `row_key` gives the key of a buffer row and `col_index` the visible
+    /// position of a column id.
+    pub fn resolve(
+        &self,
+        rows: Range<usize>,
+        last: &ResolvedSelection,
+        row_key: &dyn Fn(usize) -> Option<RowKey>,
+        col_index: &dyn Fn(&str) -> Option<usize>,
+    ) -> ResolvedSelection {
+        let mut resolved = ResolvedSelection::default();
+        if self.is_empty() {
+            return resolved;
+        }
+        let (mut cursor_row, mut anchor_row) = (None, None);
+        let selected: HashSet<&RowKey> = self.rows.iter().collect();
+        for row in rows.clone() {
+            let Some(key) = row_key(row) else {
+                continue;
+            };
+            if self.cursor.as_ref().is_some_and(|c| c.row == key) {
+                cursor_row = Some(row);
+            }
+            if self.anchor.as_ref().is_some_and(|c| c.row == key) {
+                anchor_row = Some(row);
+            }
+            if selected.contains(&key) {
+                resolved.rows.push(row);
+            }
+        }
+        let last_rows = last
+            .cursor
+            .map(|(row, _)| row)
+            .into_iter()
+            .chain(last.range.into_iter().flat_map(|((r0, r1), _)| [r0, r1]))
+            .filter(|row| !rows.contains(row));
+        for row in last_rows {
+            let Some(key) = row_key(row) else {
+                continue;
+            };
+            let is = |cell: &Option<CellRef>| cell.as_ref().is_some_and(|c| c.row == key);
+            if cursor_row.is_none() && is(&self.cursor) {
+                cursor_row = Some(row);
+            }
+            if anchor_row.is_none() && is(&self.anchor) {
+                anchor_row = Some(row);
+            }
+        }
+        let cursor_col = self.cursor.as_ref().and_then(|c| col_index(&c.col));
+        let anchor_col = self.anchor.as_ref().and_then(|c| col_index(&c.col));
+        if let (Some(row), Some(col)) = (cursor_row, cursor_col) {
+            resolved.cursor = Some((row, col));
+            let (anchor_row, anchor_col) = match (anchor_row, anchor_col) {
+                (Some(r), Some(c)) => (r, c),
+                _ => (row, col),
+            };
+            resolved.range = Some((
+                (row.min(anchor_row), row.max(anchor_row)),
+                (col.min(anchor_col), col.max(anchor_col)),
+            ));
+        }
+        resolved
+    }
+
+    /// Moves the cursor and returns its new buffer row, shift extends the range.
+    /// Starts at the top left cell when nothing is selected.
+    #[allow(clippy::too_many_arguments)]
+    pub fn navigate(
+        &mut self,
+        nav: Nav,
+        shift: bool,
+        resolved: &ResolvedSelection,
+        row_count: usize,
+        col_count: usize,
+        page: usize,
+        row_key: &dyn Fn(usize) -> Option<RowKey>,
+        col_id: &dyn Fn(usize) -> Option<String>,
+    ) -> Option<usize> {
+        if row_count == 0 || col_count == 0 {
+            return None;
+        }
+        let (last_row, last_col) = (row_count - 1, col_count - 1);
+        let page = page.max(1);
+        let (row, col) = match resolved.cursor {
+            None => (0, 0),
+            Some((row, col)) => match nav {
+                Nav::Up => (row.saturating_sub(1), col),
+                Nav::Down => ((row + 1).min(last_row), col),
+                Nav::Left => (row, col.saturating_sub(1)),
+                Nav::Right => (row, (col + 1).min(last_col)),
+                Nav::PageUp => (row.saturating_sub(page), col),
+                Nav::PageDown => ((row + page).min(last_row), col),
+                Nav::Home => (row, 0),
+                Nav::End => (row, last_col),
+                Nav::First => (0, col),
+                Nav::Last => (last_row, col),
+            },
+        };
+        let cell = CellRef {
+            row: row_key(row)?,
+            col: col_id(col)?,
+        };
+        self.click(cell, shift, false);
+        Some(row)
+    }
+}
+
+/// New `top_index` that keeps `row` within a page of `page` rows.
+pub fn scroll_to(row: usize, top_index: usize, page: usize) -> usize {
+    if row < top_index {
+        row
+    } else if row >= top_index + page.max(1) {
+        row + 1 - page.max(1)
+    } else {
+        top_index
+    }
+}
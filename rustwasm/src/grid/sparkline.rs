@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use crate::grid::theme::*;
 use crate::utils::set_panic_hook;
 use std::f64;
 use wasm_bindgen::prelude::*;
@@ -15,25 +16,19 @@ macro_rules! _console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Default)]
 #[repr(usize)]
 enum Color {
     Red = 0,
     Green = 1,
+    #[default]
     Gray = 2,
 }
 
-impl Default for Color {
-    fn default() -> Self {
-        Color::Gray
-    }
-}
-
 const MARGIN: f64 = 5_f64;
-static COLORS: [&str; 3] = ["#ff0000", "#008000", "#232832"];
 
 #[derive(Default)]
-pub struct Sparkline {
+pub struct Sparkline<'t> {
     pub x: f64,
     pub y: f64,
     pub width: f64,
@@ -44,16 +39,23 @@ pub struct Sparkline {
     pub range_y: f64,
     pub step_x: f64,
     last_c: Color,
+    colors: [&'t str; 3], // indexed by Color, borrowed from the theme
 }
 
-impl Sparkline {
-    pub fn new() -> Sparkline {
+impl<'t> Sparkline<'t> {
+    pub fn new() -> Sparkline<'t> {
         set_panic_hook();
         Sparkline {
+            colors: Theme::dark_ref().spark_colors(),
             ..Default::default()
         }
     }
 
+    pub fn with_theme(mut self, theme: &'t Theme) -> Sparkline<'t> {
+        self.colors = theme.spark_colors();
+        self
+    }
+
     pub fn render(
         &mut self,
         ctx: &CanvasRenderingContext2d,
@@ -87,8 +89,7 @@ impl Sparkline {
         let mut last_x = self.x;
         let mut next_x = self.x;
 
-        for i in 1..sparks.len() {
-            let v = sparks[i];
+        for &v in &sparks[1..] {
             let next_y = self.pos_y(v);
             let next_c = color_index(v_0, v);
             next_x += self.step_x;
@@ -140,7 +141,7 @@ impl Sparkline {
     ) {
         ctx.begin_path();
         if self.last_c != color_index {
-            ctx.set_stroke_style(&COLORS[color_index as usize].into());
+            ctx.set_stroke_style_str(self.colors[color_index as usize]);
             self.last_c = color_index;
         }
 
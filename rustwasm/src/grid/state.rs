@@ -0,0 +1,154 @@
+use crate::error::*;
+use crate::grid::schema::*;
+use serde::{Deserialize, Serialize};
+
+/// Bumped when a field changes meaning, older documents are still accepted.
+pub const STATE_VERSION: u32 = 1;
+
+/// What the user changed about a grid, persisted by the host between sessions.
+/// Sort, filters and group-by are applied by the feeder, the grid only keeps them.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ViewState {
+    pub version: u32,
+    pub columns: Vec<ColumnState>, // display order
+    #[serde(flatten)]
+    pub query: ViewQuery,
+    pub top_index: usize,
+    pub row_height: usize, // 0 - from the theme fonts
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ViewQuery {
+    pub sort: Vec<SortKey>, // first key sorts first
+    pub filters: Vec<Filter>,
+    pub group_by: Vec<String>, // column ids
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ColumnState {
+    pub id: String,
+    pub width: f64, // 0 - shares the remaining space
+    pub hidden: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SortKey {
+    pub col: String,
+    pub descending: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Filter {
+    pub col: String,
+    pub op: FilterOp,
+    pub value: serde_json::Value,
+}
+
+impl ViewQuery {
+    /// Header click: sorts by the column ascending, then descending, then unsorted.
+    pub fn toggle_sort(&mut self, col: &str) {
+        let next = match self.sort.as_slice() {
+            [key] if key.col == col && key.descending => None,
+            [key] if key.col == col => Some(true),
+            _ => Some(false),
+        };
+        self.sort = next
+            .map(|descending| SortKey {
+                col: col.to_string(),
+                descending,
+            })
+            .into_iter()
+            .collect();
+    }
+}
+
+impl ViewState {
+    /// Column order, widths and hidden flags of `schema`.
+    pub fn capture(schema: &Schema) -> ViewState {
+        ViewState {
+            version: STATE_VERSION,
+            columns: schema
+                .get_display_cols()
+                .map(|col| ColumnState {
+                    id: col.id.to_string(),
+                    width: col.width,
+                    hidden: col.hidden,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> GridResult<ViewState> {
+        let state: ViewState = serde_json::from_str(json).map_err(|e| {
+            GridError::new(ErrorKind::InvalidArgument, format!("invalid state: {}", e))
+        })?;
+        if state.version == 0 || state.version > STATE_VERSION {
+            return Err(GridError::new(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "unsupported state version {}, expected 1 to {}",
+                    state.version, STATE_VERSION
+                ),
+            ));
+        }
+        Ok(state)
+    }
+
+    /// Applies the column layout to `schema`. Columns the state doesn't know keep
+    /// their settings and follow the restored ones, unknown ids are ignored, and so
+    /// are sort keys, filters and group-by columns that no longer exist.
+    pub fn restore(mut self, schema: &mut Schema) -> GridResult<ViewState> {
+        let mut restored = schema.clone();
+        let mut order: Vec<usize> = Vec::with_capacity(restored.cols.len());
+        let known = self
+            .columns
+            .iter()
+            .filter_map(|c| restored.get_col_index(&c.id));
+        let current = (0..restored.cols.len()).map(|i| restored.order.get(i).copied().unwrap_or(i));
+        for index in known.chain(current) {
+            if !order.contains(&index) {
+                order.push(index);
+            }
+        }
+        for state in &self.columns {
+            if let Some(index) = restored.get_col_index(&state.id) {
+                let col = &mut restored.cols[index];
+                col.hidden = state.hidden;
+                if state.width.is_finite() && state.width >= 0.0 {
+                    col.width = state.width;
+                }
+            }
+        }
+        restored.order = order;
+        normalize_schema(&mut restored)?;
+        *schema = restored;
+
+        let known = |id: &str| schema.get_col_by_id(id).is_some();
+        self.columns.retain(|c| known(&c.id));
+        self.query.sort.retain(|k| known(&k.col));
+        self.query.filters.retain(|f| known(&f.col));
+        self.query.group_by.retain(|id| known(id));
+        Ok(self)
+    }
+}
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// String table for dictionary-encoded `Symbol` columns.
+/// Rows store a u32 index, the strings are registered once and shared across frames.
+#[derive(Debug, Default, Clone)]
+pub struct SymbolTable {
+    strings: Vec<String>,
+    index: HashMap<String, u32>,
+    // alphabetical rank of each index for cheap sorting, equal strings share one.
+    // Sorted on the first `rank` after the table grew, it only ever appends.
+    ranks: RefCell<Vec<u32>>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable {
+            ..Default::default()
+        }
+    }
+
+    pub fn from_strings(strings: Vec<String>) -> SymbolTable {
+        let mut table = SymbolTable::new();
+        table.extend(strings);
+        table
+    }
+
+    /// Appends strings in order, so indices already handed out stay valid.
+    pub fn extend(&mut self, strings: Vec<String>) {
+        for s in strings {
+            self.push(s);
+        }
+    }
+
+    /// Returns the index of `s`, adding it to the table if needed.
+    pub fn intern(&mut self, s: &str) -> u32 {
+        match self.index.get(s) {
+            Some(&i) => i,
+            None => self.push(s.to_string()),
+        }
+    }
+
+    fn push(&mut self, s: String) -> u32 {
+        let i = self.strings.len() as u32;
+        // duplicates keep their own slot and the rank of the first, intern returns the first
+        self.index.entry(s.clone()).or_insert(i);
+        self.strings.push(s);
+        i
+    }
+
+    fn sorted_ranks(&self) -> Vec<u32> {
+        let text = |i: u32| self.strings[i as usize].as_str();
+        let mut order: Vec<u32> = (0..self.strings.len() as u32).collect();
+        order.sort_by(|&a, &b| text(a).cmp(text(b)));
+        let mut ranks = vec![0; order.len()];
+        let mut rank = 0;
+        for (n, &i) in order.iter().enumerate() {
+            if n > 0 && text(order[n - 1]) != text(i) {
+                rank += 1;
+            }
+            ranks[i as usize] = rank;
+        }
+        ranks
+    }
+
+    pub fn get(&self, i: u32) -> Option<&str> {
+        self.strings.get(i as usize).map(|s| s.as_str())
+    }
+
+    pub fn rank(&self, i: u32) -> Option<u32> {
+        if self.ranks.borrow().len() != self.strings.len() {
+            *self.ranks.borrow_mut() = self.sorted_ranks();
+        }
+        self.ranks.borrow().get(i as usize).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
@@ -0,0 +1,366 @@
+use crate::error::*;
+use crate::grid::column::*;
+use crate::utils::*;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use wasm_bindgen::prelude::*;
+
+/// Colors used by `Grid` and `DOB`, any CSS color. Missing fields
+/// keep the dark preset value, so `{ gridline: "#444" }` is a valid theme.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Theme {
+    pub background: String,        // canvas outside the client area
+    pub client_background: String, // cells
+    pub text: String,
+    pub header_text: String,
+    pub highlight_text: String, // recently updated cells
+    pub highlight_background: String,
+    pub gridline: String,
+    pub selection_background: String,
+    pub focus: String,   // outline of the cursor cell
+    pub bid_bar: String, // DOB cumulative size bars
+    pub ask_bar: String,
+    pub spark_up: String,
+    pub spark_down: String,
+    pub spark_flat: String,
+    pub font: String, // CSS font shorthand, e.g. "16px sans-serif"
+    pub header_font: String,
+    pub numeric_font: String, // Number, Decimal and Duration cells
+}
+
+const DEFAULT_FONT: &str = "16px sans-serif";
+const DEFAULT_NUMERIC_FONT: &str = "16px monospace";
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            background: "#0b0e17".to_string(),
+            client_background: "#101722".to_string(),
+            text: "#03c67a".to_string(),
+            header_text: "#03c67a".to_string(),
+            highlight_text: "white".to_string(),
+            highlight_background: "#ffffff22".to_string(),
+            gridline: "#232832".to_string(),
+            selection_background: "#03c67a33".to_string(),
+            focus: "#03c67a".to_string(),
+            bid_bar: "#0c433899".to_string(),
+            ask_bar: "#ff3b6960".to_string(),
+            spark_up: "#008000".to_string(),
+            spark_down: "#ff0000".to_string(),
+            spark_flat: "#232832".to_string(),
+            font: DEFAULT_FONT.to_string(),
+            header_font: DEFAULT_FONT.to_string(),
+            numeric_font: DEFAULT_NUMERIC_FONT.to_string(),
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            background: "#e9ecf1".to_string(),
+            client_background: "#ffffff".to_string(),
+            text: "#1b2230".to_string(),
+            header_text: "#0a7a4d".to_string(),
+            highlight_text: "#000000".to_string(),
+            highlight_background: "#0000001a".to_string(),
+            gridline: "#d5dae3".to_string(),
+            selection_background: "#0a7a4d26".to_string(),
+            focus: "#0a7a4d".to_string(),
+            bid_bar: "#03c67a40".to_string(),
+            ask_bar: "#ff3b6940".to_string(),
+            spark_up: "#0a8f3c".to_string(),
+            spark_down: "#d6204e".to_string(),
+            spark_flat: "#9aa3b2".to_string(),
+            ..Theme::dark()
+        }
+    }
+
+    pub fn preset(name: &str) -> Result<Theme, String> {
+        match name {
+            "dark" => Ok(Theme::dark()),
+            "light" => Ok(Theme::light()),
+            _ => Err(format!("unknown theme '{}', expected dark or light", name)),
+        }
+    }
+
+    /// Shared dark theme for renderers created without one.
+    pub fn dark_ref() -> &'static Theme {
+        static DARK: OnceLock<Theme> = OnceLock::new();
+        DARK.get_or_init(Theme::dark)
+    }
+
+    /// Sparkline colors indexed by direction: down, up, flat.
+    pub fn spark_colors(&self) -> [&str; 3] {
+        [&self.spark_down, &self.spark_up, &self.spark_flat]
+    }
+
+    /// Font of a column's cells with its weight and style, `bold` forces bold.
+    pub fn cell_font(&self, col: &Column, bold: bool) -> String {
+        let base = if col.shows_number() {
+            &self.numeric_font
+        } else {
+            &self.font
+        };
+        let mut font = String::with_capacity(base.len() + 12);
+        if col.italic {
+            font.push_str("italic ");
+        }
+        if bold || col.bold {
+            font.push_str("bold ");
+        }
+        font.push_str(base);
+        font
+    }
+
+    /// Fails on the first field that is not a CSS color or font.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, font) in [
+            ("font", &self.font),
+            ("header_font", &self.header_font),
+            ("numeric_font", &self.numeric_font),
+        ] {
+            if !is_valid_font(font) {
+                return Err(format!(
+                    "invalid font '{}' for {}, expected a size and a family like '{}'",
+                    font, name, DEFAULT_FONT
+                ));
+            }
+        }
+        let fields = [
+            ("background", &self.background),
+            ("client_background", &self.client_background),
+            ("text", &self.text),
+            ("header_text", &self.header_text),
+            ("highlight_text", &self.highlight_text),
+            ("highlight_background", &self.highlight_background),
+            ("gridline", &self.gridline),
+            ("selection_background", &self.selection_background),
+            ("focus", &self.focus),
+            ("bid_bar", &self.bid_bar),
+            ("ask_bar", &self.ask_bar),
+            ("spark_up", &self.spark_up),
+            ("spark_down", &self.spark_down),
+            ("spark_flat", &self.spark_flat),
+        ];
+        for (name, color) in fields.iter() {
+            if !is_valid_color(color) {
+                return Err(format!("invalid color '{}' for {}", color, name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A preset name ("dark", "light") or a theme object, validated.
+pub fn theme_from_js(obj: &JsValue) -> GridResult<Theme> {
+    let theme = match obj.as_string() {
+        Some(name) => Theme::preset(&name),
+        None => Ok(from_js::<Theme>(obj)?),
+    };
+    theme
+        .and_then(|theme| theme.validate().map(|_| theme))
+        .map_err(|e| GridError::new(ErrorKind::InvalidArgument, e))
+}
+
+/// CSS font shorthand needs a size (px, pt, em, rem or %) followed by a family.
+pub fn is_valid_font(font: &str) -> bool {
+    let tokens: Vec<&str> = font.split_whitespace().collect();
+    tokens.iter().enumerate().any(|(i, token)| {
+        let size = token.split('/').next().unwrap_or_default(); // "16px/1.2"
+        let number = ["px", "pt", "rem", "em", "%"]
+            .iter()
+            .find_map(|unit| size.strip_suffix(unit));
+        matches!(number, Some(n) if n.parse::<f64>().is_ok_and(|n| n > 0.0)) && i + 1 < tokens.len()
+    })
+}
+
+/// Accepts hex (#rgb, #rgba, #rrggbb, #rrggbbaa), rgb()/rgba()/hsl()/hsla()
+/// and CSS named colors. Function arguments are only checked for shape.
+pub fn is_valid_color(color: &str) -> bool {
+    let color = color.trim();
+    if let Some(hex) = color.strip_prefix('#') {
+        return matches!(hex.len(), 3 | 4 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    let lower = color.to_ascii_lowercase();
+    if let Some(open) = lower.find('(') {
+        let name = &lower[..open];
+        let Some(args) = lower[open + 1..].strip_suffix(')') else {
+            return false;
+        };
+        let args: Vec<&str> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|a| !a.is_empty())
+            .collect();
+        let numeric = args.iter().all(|a| {
+            let a = a
+                .trim_end_matches('%')
+                .trim_end_matches("deg")
+                .trim_end_matches("turn");
+            !a.is_empty() && a.parse::<f64>().is_ok()
+        });
+        return matches!(name, "rgb" | "rgba" | "hsl" | "hsla")
+            && numeric
+            && matches!(args.len(), 3 | 4);
+    }
+    NAMED_COLORS.binary_search(&lower.as_str()).is_ok()
+}
+
+// sorted for binary_search
+const NAMED_COLORS: [&str; 150] = [
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "currentcolor",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "transparent",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
@@ -0,0 +1,81 @@
+use crate::grid::column::*;
+use crate::grid::ds::*;
+use crate::grid::locale::*;
+use crate::grid::numfmt::*;
+use crate::grid::pointer::*;
+use crate::grid::schema::*;
+use serde::Serialize;
+
+/// Tooltip text and the canvas rect it belongs to, for the host to position it.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Tooltip {
+    pub text: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// "min 1.00 · max 3.00 · last 2.00", empty for no values.
+pub fn sparkline_summary(values: &[f64], precision: usize, locale: &Locale) -> String {
+    let (Some(first), Some(last)) = (values.first(), values.last()) else {
+        return String::new();
+    };
+    let (min, max) = values
+        .iter()
+        .fold((*first, *first), |(min, max), &v| (min.min(v), max.max(v)));
+    let fmt = |v: f64| NumberFormat::DEFAULT.format_f64(v, precision, "", "", locale);
+    format!("min {} · max {} · last {}", fmt(min), fmt(max), fmt(*last))
+}
+
+/// Untruncated text of a cell, sparklines get their min, max and last.
+pub fn tooltip_text(
+    ds: &DataSource,
+    row: usize,
+    col: &Column,
+    format: &FormatContext,
+    null_text: &str,
+) -> String {
+    match col.col_type {
+        ColumnType::Sparkline => ds
+            .get_sparkline(row, col)
+            .map(|values| sparkline_summary(values, col.precision, format.locale))
+            .unwrap_or_default(),
+        _ => ds
+            .format_cell(row, col, format, null_text)
+            .map(|text| text.into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+/// Column description over the header, full cell text over the data rows.
+/// `cell_text` gets the visible row and column of the hit cell only.
+pub fn tooltip_at(
+    header: &HeaderLayout,
+    schema: &Schema,
+    cell_text: impl Fn(usize, &Column) -> Option<String>,
+    x: f64,
+    y: f64,
+) -> Option<Tooltip> {
+    let col = header.col_at(x)?;
+    let (left, right) = (header.edges[col], header.edges[col + 1]);
+    if y >= header.top && y < header.bottom {
+        let description = &schema.get_col_by_id(&header.ids[col])?.description;
+        return (!description.is_empty()).then(|| Tooltip {
+            text: description.to_string(),
+            x: left,
+            y: header.top,
+            width: right - left,
+            height: header.bottom - header.top,
+        });
+    }
+    let (row, _) = header.data_cell_at(x, y)?;
+    let text = cell_text(row, schema.get_col_by_id(&header.ids[col])?)?;
+    (!text.is_empty()).then_some(Tooltip {
+        text,
+        x: left,
+        y: header.bottom + row as f64 * header.row_height,
+        width: right - left,
+        height: header.row_height,
+    })
+}
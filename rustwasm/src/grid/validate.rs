@@ -0,0 +1,214 @@
+use crate::grid::column::*;
+use crate::grid::datefmt::*;
+use crate::grid::decimal::*;
+use crate::grid::numfmt::*;
+use crate::grid::renderer::*;
+use crate::grid::schema::*;
+use chrono::format::{Item, StrftimeItems};
+use serde::Serialize;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+const ALIGN_VALUES: [&str; 4] = ["", "left", "center", "right"];
+
+/// One schema problem, `path` points into the schema JSON, e.g. `$.cols[2].format`.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct SchemaProblem {
+    pub path: String,
+    pub message: String,
+    pub column: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ValidateOptions<'a> {
+    /// `None` when the sparks are not known yet - sparkline columns are not checked.
+    pub has_sparks: Option<bool>,
+    /// Column ids the component depends on, e.g. `cumSize` for the DOB.
+    pub required_cols: &'a [&'a str],
+}
+
+/// Reports every problem at once, unlike `normalize_schema` which stops at the first one.
+pub fn validate_schema(schema: &Schema, options: &ValidateOptions) -> Vec<SchemaProblem> {
+    let mut problems = vec![];
+    let mut report = |path: String, column: Option<&str>, message: String| {
+        problems.push(SchemaProblem {
+            path,
+            message,
+            column: column.map(String::from),
+        })
+    };
+
+    let mut first_index: HashMap<&str, usize> = HashMap::new();
+    for (index, col) in schema.cols.iter().enumerate() {
+        let path = |field: &str| format!("$.cols[{}].{}", index, field);
+        let id = if col.id.is_empty() {
+            None
+        } else {
+            Some(col.id.as_str())
+        };
+
+        match id {
+            None => report(path("id"), None, "column id is empty".to_string()),
+            Some(id) => {
+                if let Some(first) = first_index.get(id) {
+                    report(
+                        path("id"),
+                        Some(id),
+                        format!(
+                            "duplicate column id '{}', first used by $.cols[{}]",
+                            id, first
+                        ),
+                    );
+                } else {
+                    first_index.insert(id, index);
+                }
+            }
+        }
+
+        match col.col_type {
+            ColumnType::String | ColumnType::Sparkline if col.size == 0 => report(
+                path("size"),
+                id,
+                "string column size must be greater than zero".to_string(),
+            ),
+            ColumnType::String | ColumnType::Sparkline | ColumnType::Symbol => {}
+            _ if col.size != num_size() => report(
+                path("size"),
+                id,
+                format!(
+                    "numeric column size must be {}, got {}",
+                    num_size(),
+                    col.size
+                ),
+            ),
+            _ => {}
+        }
+
+        if col.scale > MAX_SCALE {
+            report(
+                path("scale"),
+                id,
+                format!("scale {} exceeds the maximum of {}", col.scale, MAX_SCALE),
+            );
+        }
+
+        if !ALIGN_VALUES.contains(&col.align.as_str()) {
+            report(
+                path("align"),
+                id,
+                format!(
+                    "unknown align '{}', expected left, center or right",
+                    col.align
+                ),
+            );
+        }
+
+        if is_date_type(col.col_type)
+            && !col.format.is_empty()
+            && !is_valid_date_format(&col.format)
+        {
+            report(
+                path("format"),
+                id,
+                format!("invalid date format '{}'", col.format),
+            );
+        }
+
+        if is_date_type(col.col_type) {
+            if let Err(e) = Zone::parse(&col.timezone) {
+                report(path("timezone"), id, e);
+            }
+        }
+
+        if col.col_type == ColumnType::Duration {
+            if let Err(e) = DurationStyle::parse(&col.format) {
+                report(path("format"), id, e);
+            }
+        }
+
+        if col.is_numeric() {
+            if let Err(e) = NumberFormat::parse(&col.format) {
+                report(path("format"), id, e);
+            }
+        }
+
+        if let Some(price) = &col.price {
+            if let Err(e) = price.validate() {
+                report(path("price"), id, e);
+            }
+        }
+
+        if col.col_type == ColumnType::Sparkline && options.has_sparks == Some(false) {
+            report(
+                path("col_type"),
+                id,
+                "sparkline column has no sparks, call set_sparks first".to_string(),
+            );
+        }
+    }
+
+    if !schema.cols.is_empty() && schema.cols.iter().all(|c| c.hidden) {
+        report(
+            "$.cols".to_string(),
+            None,
+            "all columns are hidden".to_string(),
+        );
+    }
+
+    for &id in options.required_cols {
+        if schema.get_col_by_id(id).is_none() {
+            report(
+                "$.cols".to_string(),
+                Some(id),
+                format!("required column '{}' is missing", id),
+            );
+        }
+    }
+
+    problems
+}
+
+pub fn is_date_type(col_type: ColumnType) -> bool {
+    matches!(
+        col_type,
+        ColumnType::Date | ColumnType::DateTime | ColumnType::Timestamp
+    )
+}
+
+pub fn is_valid_date_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| item == Item::Error)
+}
+
+/// Validates a schema object before creating a `Grid`, returns an array of problems.
+#[wasm_bindgen]
+pub fn validate_grid_schema(schema: &JsValue) -> Result<JsValue, JsValue> {
+    validate_js(schema, &ValidateOptions::default())
+}
+
+/// Same as `validate_grid_schema`, including the columns the DOB depends on.
+#[wasm_bindgen]
+pub fn validate_dob_schema(schema: &JsValue) -> Result<JsValue, JsValue> {
+    validate_js(
+        schema,
+        &ValidateOptions {
+            required_cols: &[CUM_SIZE_COL_ID],
+            ..Default::default()
+        },
+    )
+}
+
+fn validate_js(obj: &JsValue, options: &ValidateOptions) -> Result<JsValue, JsValue> {
+    let problems = match serde_wasm_bindgen::from_value::<Schema>(obj.clone()) {
+        Ok(schema) => validate_schema(&schema, options),
+        Err(e) => vec![SchemaProblem {
+            path: "$".to_string(),
+            message: e.to_string(),
+            column: None,
+        }],
+    };
+    problems_to_js(&problems)
+}
+
+pub fn problems_to_js(problems: &[SchemaProblem]) -> Result<JsValue, JsValue> {
+    Ok(serde_wasm_bindgen::to_value(problems)?)
+}
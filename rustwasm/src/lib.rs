@@ -1,6 +1,7 @@
 use wasm_bindgen::prelude::*;
 
 mod dob;
+pub mod error;
 pub mod grid;
 mod utils;
 
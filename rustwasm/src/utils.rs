@@ -1,10 +1,11 @@
-use getrandom;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{
     CanvasRenderingContext2d, Document, HtmlCanvasElement, HtmlElement, Performance, Window,
 };
 
+use crate::error::*;
+use serde::de::DeserializeOwned;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -30,42 +31,59 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
-pub fn _body() -> HtmlElement {
-    document().body().expect("document should have a body")
+pub fn _body() -> GridResult<HtmlElement> {
+    document()?
+        .body()
+        .ok_or_else(|| GridError::new(ErrorKind::MissingElement, "document has no body"))
 }
 
-pub fn window() -> Window {
-    web_sys::window().expect("no global `window` exists")
+pub fn window() -> GridResult<Window> {
+    web_sys::window()
+        .ok_or_else(|| GridError::new(ErrorKind::MissingElement, "no global `window` exists"))
 }
 
-pub fn document() -> Document {
-    window()
+pub fn document() -> GridResult<Document> {
+    window()?
         .document()
-        .expect("should have a document on window")
+        .ok_or_else(|| GridError::new(ErrorKind::MissingElement, "window has no document"))
 }
 
-pub fn canvas(id: &str) -> HtmlCanvasElement {
-    document()
+pub fn canvas(id: &str) -> GridResult<HtmlCanvasElement> {
+    document()?
         .get_element_by_id(id)
-        .expect("should have a canvas element")
+        .ok_or_else(|| {
+            GridError::new(
+                ErrorKind::MissingElement,
+                format!("element '{}' not found", id),
+            )
+        })?
         .dyn_into::<HtmlCanvasElement>()
-        .map_err(|_| ())
-        .unwrap()
+        .map_err(|_| {
+            GridError::new(
+                ErrorKind::MissingElement,
+                format!("element '{}' is not a canvas", id),
+            )
+        })
 }
 
-pub fn ctx(id: &str) -> CanvasRenderingContext2d {
-    canvas(id)
+pub fn ctx(id: &str) -> GridResult<CanvasRenderingContext2d> {
+    canvas(id)?
         .get_context("2d")
-        .unwrap()
-        .unwrap()
-        .dyn_into::<CanvasRenderingContext2d>()
-        .unwrap()
+        .ok()
+        .flatten()
+        .and_then(|o| o.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or_else(|| {
+            GridError::new(
+                ErrorKind::Canvas,
+                format!("canvas '{}' has no 2d context", id),
+            )
+        })
 }
 
-pub fn _performance() -> Performance {
-    window()
+pub fn _performance() -> GridResult<Performance> {
+    window()?
         .performance()
-        .expect("performance should be available")
+        .ok_or_else(|| GridError::new(ErrorKind::MissingElement, "performance is not available"))
 }
 
 // ex: let values = &get_random_255().unwrap();
@@ -75,14 +93,24 @@ pub fn _get_random_255() -> Result<[u8; 255], getrandom::Error> {
     Ok(buf)
 }
 
-pub fn _perf_loop(title: &str, count: u32, cb: &dyn Fn()) -> f64 {
-    let p1 = _performance().now();
+pub fn _perf_loop(title: &str, count: u32, cb: &dyn Fn()) -> GridResult<f64> {
+    let performance = _performance()?;
+    let p1 = performance.now();
     for _ in 0..count {
         cb();
     }
-    let msec = _performance().now() - p1;
+    let msec = performance.now() - p1;
     _console_log!("PERFORMANCE: {} in {} msecs", title, msec);
-    msec
+    Ok(msec)
+}
+
+/// Deserializes a JS argument; `null`/`undefined` yield the default value.
+pub fn from_js<T: DeserializeOwned + Default>(obj: &JsValue) -> GridResult<T> {
+    if obj.is_null() || obj.is_undefined() {
+        Ok(T::default())
+    } else {
+        Ok(serde_wasm_bindgen::from_value(obj.clone())?)
+    }
 }
 
 pub fn hash_code(v: &str) -> u64 {
@@ -92,8 +120,9 @@ pub fn hash_code(v: &str) -> u64 {
 }
 
 #[wasm_bindgen]
-pub fn _performance_test_example(name: &str) {
+pub fn _performance_test_example(name: &str) -> Result<(), JsValue> {
     _perf_loop(name, 10000, &|| {
-        let _ctx = ctx("canvas1");
-    });
+        let _ctx = ctx("canvas1").ok();
+    })?;
+    Ok(())
 }
@@ -0,0 +1,105 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::autofit::*;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+    use rustwasm::grid::theme::*;
+
+    // 10px per char, bold 12px
+    fn width(text: &str, font: &str) -> f64 {
+        let per_char = if font.starts_with("bold") { 12.0 } else { 10.0 };
+        text.chars().count() as f64 * per_char
+    }
+
+    fn col(id: &str, name: &str, col_type: ColumnType, size: usize) -> Column {
+        Column {
+            id: id.to_string(),
+            name: name.to_string(),
+            col_type,
+            size,
+            precision: 2,
+            ..Default::default()
+        }
+    }
+
+    fn schema(cols: Vec<Column>) -> Schema {
+        let mut schema = Schema {
+            cols,
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    fn prices(values: &[f64]) -> Vec<u8> {
+        let mut buf = vec![0u8; values.len() * num_size()];
+        for (i, v) in values.iter().enumerate() {
+            BigEndian::write_f64(&mut buf[i * num_size()..], *v);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_fit_rows() {
+        assert_eq!(fit_rows(FitMode::Visible, 10, 8, 5), vec![8, 9]);
+        assert_eq!(fit_rows(FitMode::Visible, 10, 20, 5), Vec::<usize>::new());
+        assert_eq!(fit_rows(FitMode::Sample, 3, 2, 1), vec![0, 1, 2]);
+        let rows = fit_rows(FitMode::Sample, 100_000, 0, 10);
+        assert_eq!(rows.len(), SAMPLE_ROWS);
+        assert_eq!((rows[0], rows[1], rows[SAMPLE_ROWS - 1]), (0, 100, 99_900));
+        // i * row_count would overflow a 32-bit usize
+        let rows = fit_rows(FitMode::Sample, u32::MAX as usize, 0, 10);
+        assert_eq!((rows[1], rows[SAMPLE_ROWS - 1]), (4_294_967, 4_290_672_327));
+        assert!(rows.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_fit_widths() {
+        let schema = schema(vec![
+            col("px", "Px", ColumnType::Number, num_size()),
+            col("sp", "Spark", ColumnType::Sparkline, 0),
+        ]);
+        let buf = prices(&[1.5, 12345.25, 7.0]);
+        let ds = DataSource::new(&buf, num_size(), None);
+        let theme = Theme::dark();
+        let fc = FormatContext::default();
+
+        // "12345.25" is the widest cell, plus padding on both sides
+        let widths = fit_widths(&schema, &ds, &[0, 1, 2], &fc, &theme, &mut width);
+        assert_eq!(widths, vec![Some(100.0), None]);
+        // "1.50" only
+        let widths = fit_widths(&schema, &ds, &[0], &fc, &theme, &mut width);
+        assert_eq!(widths[0], Some(60.0));
+        // nothing to measure but the header "Px"
+        let widths = fit_widths(&schema, &ds, &[], &fc, &theme, &mut width);
+        assert_eq!(widths[0], Some(MIN_FIT_WIDTH));
+    }
+
+    #[test]
+    fn test_fit_widths_header_and_limits() {
+        let mut bold = col("px", "Price", ColumnType::Number, num_size());
+        bold.bold = true;
+        let mut hidden = col("h", "Hidden", ColumnType::Number, num_size());
+        hidden.hidden = true;
+        let long = col("n", &"N".repeat(100), ColumnType::Number, num_size());
+        let schema = schema(vec![bold, hidden, long]);
+        let buf = prices(&[1.0, 1.0, 1.0]);
+        let ds = DataSource::new(&buf, 3 * num_size(), None);
+
+        let widths = fit_widths(
+            &schema,
+            &ds,
+            &[0],
+            &FormatContext::default(),
+            &Theme::dark(),
+            &mut width,
+        );
+        // header "Price" 50px beats bold "1.00" 48px
+        assert_eq!(widths, vec![Some(70.0), None, Some(MAX_FIT_WIDTH)]);
+    }
+}
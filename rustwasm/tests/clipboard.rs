@@ -0,0 +1,124 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::clipboard::*;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::schema::*;
+    use rustwasm::grid::selection::*;
+
+    fn schema() -> Schema {
+        let mut schema = Schema {
+            cols: vec![
+                Column {
+                    id: "sym".to_string(),
+                    name: "Symbol".to_string(),
+                    col_type: ColumnType::String,
+                    size: 8,
+                    ..Default::default()
+                },
+                Column {
+                    id: "px".to_string(),
+                    name: "Price".to_string(),
+                    col_type: ColumnType::Number,
+                    size: 8,
+                    precision: 2,
+                    format: ",".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    fn data() -> Vec<u8> {
+        let mut buf = vec![0u8; 3 * 16];
+        for (row, (sym, px)) in [("A,B", 1234.5), ("C\"D", 2.0), ("E\tF", 0.25)]
+            .iter()
+            .enumerate()
+        {
+            buf[row * 16..row * 16 + sym.len()].copy_from_slice(sym.as_bytes());
+            BigEndian::write_f64(&mut buf[row * 16 + 8..row * 16 + 16], *px);
+        }
+        buf
+    }
+
+    fn copy(resolved: &ResolvedSelection, format: ClipFormat, raw: bool, header: bool) -> String {
+        let buf = data();
+        let ds = DataSource::new(&buf, 16, None);
+        copy_cells(
+            &schema(),
+            &ds,
+            resolved,
+            &FormatContext::default(),
+            ClipOptions {
+                format,
+                raw,
+                header,
+            },
+        )
+    }
+
+    fn range(rows: (usize, usize), cols: (usize, usize)) -> ResolvedSelection {
+        ResolvedSelection {
+            cursor: Some((rows.1, cols.1)),
+            range: Some((rows, cols)),
+            rows: vec![],
+        }
+    }
+
+    #[test]
+    fn test_tsv() {
+        assert_eq!(
+            copy(&range((0, 2), (0, 1)), ClipFormat::Tsv, false, true),
+            "Symbol\tPrice\r\nA,B\t1,234.50\r\nC\"D\t2.00\r\nE F\t0.25\r\n"
+        );
+        assert_eq!(
+            copy(&range((0, 0), (1, 1)), ClipFormat::Tsv, true, false),
+            "1234.5\r\n"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            copy(&range((0, 1), (0, 1)), ClipFormat::Csv, false, false),
+            "\"A,B\",\"1,234.50\"\r\n\"C\"\"D\",2.00\r\n"
+        );
+    }
+
+    #[test]
+    fn test_html() {
+        let resolved = ResolvedSelection {
+            rows: vec![1],
+            ..Default::default()
+        };
+        assert_eq!(
+            copy(&resolved, ClipFormat::Html, true, true),
+            "<table><tr><th>Symbol</th><th>Price</th></tr><tr><td>C&quot;D</td><td>2</td></tr></table>"
+        );
+        assert_eq!(
+            copy(&ResolvedSelection::default(), ClipFormat::Html, true, true),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_selected_cells() {
+        let resolved = ResolvedSelection {
+            cursor: Some((4, 1)),
+            range: Some(((3, 4), (1, 2))),
+            rows: vec![0, 4],
+        };
+        // whole rows take every column
+        assert_eq!(selected_cells(&resolved, 3), (vec![0, 3, 4], vec![0, 1, 2]));
+        assert_eq!(
+            selected_cells(&range((3, 4), (1, 2)), 3),
+            (vec![3, 4], vec![1, 2])
+        );
+    }
+}
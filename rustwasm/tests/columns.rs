@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::error::*;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+
+    fn schema() -> Schema {
+        let mut schema = Schema {
+            cols: ["a", "b", "c", "d"]
+                .iter()
+                .map(|id| Column {
+                    id: id.to_string(),
+                    col_type: ColumnType::Number,
+                    size: num_size(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    fn visible(schema: &Schema) -> Vec<&str> {
+        schema.get_visible_cols().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_hide_and_show() {
+        let mut schema = schema();
+        schema.set_col_hidden("b", true).unwrap();
+        assert_eq!(visible(&schema), ["a", "c", "d"]);
+        assert_eq!(schema.visible_col_count, 3);
+        // the in-place update matches a full normalize
+        let mut full = schema.clone();
+        normalize_schema(&mut full).unwrap();
+        assert_eq!(schema.header_lines, full.header_lines);
+        assert_eq!(schema.layout_hash, full.layout_hash);
+        schema.set_col_hidden("b", false).unwrap();
+        assert_eq!(visible(&schema), ["a", "b", "c", "d"]);
+        assert_eq!(schema.visible_col_count, 4);
+    }
+
+    #[test]
+    fn test_move_keeps_layout() {
+        let mut schema = schema();
+        let hash = schema.layout_hash;
+        schema.move_col("d", 0).unwrap();
+        assert_eq!(visible(&schema), ["d", "a", "b", "c"]);
+        schema.move_col("a", 99).unwrap();
+        assert_eq!(visible(&schema), ["d", "b", "c", "a"]);
+        schema.set_col_hidden("b", true).unwrap();
+        assert_eq!(visible(&schema), ["d", "c", "a"]);
+        // offsets follow the schema, not the display order
+        assert_eq!(
+            schema.get_col_by_id("d").unwrap().data_offset,
+            3 * num_size()
+        );
+        assert_eq!(schema.layout_hash, hash);
+        assert_eq!(schema.get_display_index("b"), Some(1));
+    }
+
+    #[test]
+    fn test_width_and_format() {
+        let mut schema = schema();
+        schema.set_col_width("a", 120.0).unwrap();
+        assert_eq!(schema.get_col_by_id("a").unwrap().width, 120.0);
+        let err = schema.set_col_width("a", -1.0).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidArgument);
+
+        schema.set_col_format("c", ",.1f").unwrap();
+        let col = schema.get_col_by_id("c").unwrap();
+        assert_eq!(col.format_value(Some(1234.56)).unwrap(), "1,234.6");
+        // a bad format leaves the schema untouched
+        assert_eq!(
+            schema.set_col_format("c", "nope").unwrap_err().kind,
+            ErrorKind::InvalidSchema
+        );
+        assert_eq!(schema.get_col_by_id("c").unwrap().format, ",.1f");
+        assert_eq!(
+            schema.set_col_hidden("x", true).unwrap_err().kind,
+            ErrorKind::MissingColumn
+        );
+    }
+
+    #[test]
+    fn test_invalid_order() {
+        let mut schema = schema();
+        schema.order = vec![0, 0, 1, 2];
+        assert!(normalize_schema(&mut schema).is_err());
+        schema.order = vec![3, 2, 1];
+        assert!(normalize_schema(&mut schema).is_err());
+        schema.order = vec![3, 2, 1, 0];
+        normalize_schema(&mut schema).unwrap();
+        assert_eq!(visible(&schema), ["d", "c", "b", "a"]);
+    }
+}
@@ -0,0 +1,132 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::datefmt::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+
+    // 2021-03-04 05:06:07.089 UTC
+    const MS: f64 = 1_614_834_367_089.0;
+
+    fn fc(clock: &FixedClock) -> FormatContext<'_> {
+        FormatContext {
+            clock,
+            ..Default::default()
+        }
+    }
+
+    fn clock(offset_hours: i32) -> FixedClock {
+        FixedClock {
+            now_ms: MS,
+            offset: FixedOffset::east_opt(offset_hours * 3600).unwrap(),
+        }
+    }
+
+    fn col(timezone: &str, epoch_unit: EpochUnit, format: &str) -> Column {
+        let mut schema = Schema {
+            cols: vec![Column {
+                id: "time".to_string(),
+                col_type: ColumnType::Timestamp,
+                size: num_size(),
+                timezone: timezone.to_string(),
+                epoch_unit,
+                format: format.to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema.cols.remove(0)
+    }
+
+    #[test]
+    fn test_zones() {
+        let f = "%Y-%m-%d %H:%M:%S";
+        let c = clock(-5);
+        let fmt = |zone: &str| {
+            col(zone, EpochUnit::Millis, f)
+                .format_value_with(Some(MS), &fc(&c))
+                .unwrap()
+        };
+        assert_eq!(fmt("UTC"), "2021-03-04 05:06:07");
+        assert_eq!(fmt("+05:30"), "2021-03-04 10:36:07");
+        assert_eq!(fmt("-0800"), "2021-03-03 21:06:07");
+        assert_eq!(fmt("Asia/Tokyo"), "2021-03-04 14:06:07");
+        assert_eq!(fmt(""), "2021-03-04 00:06:07");
+    }
+
+    #[test]
+    fn test_named_zone_dst() {
+        let col = col("America/New_York", EpochUnit::Seconds, "%H:%M %Z");
+        let c = clock(0);
+        // 2021-01-15 and 2021-07-15 12:00 UTC
+        assert_eq!(
+            col.format_value_with(Some(1_610_712_000.0), &fc(&c))
+                .unwrap(),
+            "07:00 EST"
+        );
+        assert_eq!(
+            col.format_value_with(Some(1_626_350_400.0), &fc(&c))
+                .unwrap(),
+            "08:00 EDT"
+        );
+    }
+
+    #[test]
+    fn test_epoch_units_and_fractions() {
+        let c = clock(0);
+        let f = "%H:%M:%S%.6f";
+        let fmt = |unit: EpochUnit, v: f64| col("UTC", unit, f).format_value_with(Some(v), &fc(&c));
+        assert_eq!(fmt(EpochUnit::Millis, MS).unwrap(), "05:06:07.089000");
+        assert_eq!(
+            fmt(EpochUnit::Seconds, 1_614_834_367.5).unwrap(),
+            "05:06:07.500000"
+        );
+        assert_eq!(
+            fmt(EpochUnit::Micros, 1_614_834_367_089_123.0).unwrap(),
+            "05:06:07.089123"
+        );
+        assert_eq!(
+            fmt(EpochUnit::Nanos, 1_614_834_367_089_123_000.0).unwrap(),
+            "05:06:07.089123"
+        );
+        // before the epoch the fraction still counts forward
+        assert_eq!(fmt(EpochUnit::Millis, -1.0).unwrap(), "23:59:59.999000");
+    }
+
+    #[test]
+    fn test_epoch_to_utc() {
+        let dt = epoch_to_utc(MS, EpochUnit::Millis).unwrap();
+        assert_eq!(dt.timestamp_millis(), MS as i64);
+        assert!(epoch_to_utc(f64::NAN, EpochUnit::Millis).is_none());
+        assert!(epoch_to_utc(1e300, EpochUnit::Seconds).is_none());
+    }
+
+    #[test]
+    fn test_zone_parse() {
+        assert_eq!(Zone::parse("").unwrap(), Zone::Local);
+        assert_eq!(Zone::parse("UTC").unwrap(), Zone::Utc);
+        assert_eq!(
+            Zone::parse("+9").unwrap(),
+            Zone::Fixed(FixedOffset::east_opt(9 * 3600).unwrap())
+        );
+        assert!(Zone::parse("+05:75").is_err());
+        assert!(Zone::parse("Mars/Olympus").is_err());
+
+        let mut schema = Schema {
+            cols: vec![Column {
+                id: "time".to_string(),
+                col_type: ColumnType::Date,
+                size: num_size(),
+                timezone: "Nowhere".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let err = normalize_schema(&mut schema).unwrap_err();
+        assert_eq!(err.column.as_deref(), Some("time"));
+    }
+}
@@ -0,0 +1,112 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::decimal::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::renderer::*;
+
+    fn decimal_col(scale: u32, precision: usize) -> Column {
+        Column {
+            id: "price".to_string(),
+            col_type: ColumnType::Decimal,
+            size: num_size(),
+            scale,
+            precision,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decimal_format_exact() {
+        // 0.1 + 0.2 style artefacts must not show up
+        let v = Decimal::new(1, 8) + Decimal::new(2, 8);
+        assert_eq!(v.format(8), "0.00000003");
+        assert_eq!(Decimal::new(12345678901, 8).format(8), "123.45678901");
+        assert_eq!(Decimal::new(-5, 2).format(2), "-0.05");
+        assert_eq!(Decimal::new(42, 0).format(3), "42.000");
+    }
+
+    #[test]
+    fn test_decimal_format_rounding() {
+        assert_eq!(Decimal::new(12345, 3).format(2), "12.35");
+        assert_eq!(Decimal::new(-12345, 3).format(2), "-12.35");
+        assert_eq!(Decimal::new(12344, 3).format(2), "12.34");
+        assert_eq!(Decimal::new(-4, 3).format(2), "0.00");
+        assert_eq!(Decimal::new(995, 3).format(0), "1");
+    }
+
+    #[test]
+    fn test_decimal_ordering_across_scales() {
+        assert_eq!(Decimal::new(150, 2), Decimal::new(15, 1));
+        assert!(Decimal::new(1501, 3) > Decimal::new(150, 2));
+        assert!(Decimal::new(-1, 8) < Decimal::zero(0));
+        let mut v = [
+            Decimal::new(300, 2),
+            Decimal::new(-1, 0),
+            Decimal::new(25, 1),
+        ];
+        v.sort();
+        assert_eq!(
+            v.iter().map(|d| d.format(2)).collect::<Vec<_>>(),
+            ["-1.00", "2.50", "3.00"]
+        );
+    }
+
+    #[test]
+    fn test_decimal_sum() {
+        let total: Decimal = [Decimal::new(10, 1), Decimal::new(5, 2), Decimal::new(1, 8)]
+            .iter()
+            .copied()
+            .sum();
+        assert_eq!(total.scale, 8);
+        assert_eq!(total.format(8), "1.05000001");
+    }
+
+    #[test]
+    fn test_decimal_overflow() {
+        // formatting widens past i64 without losing digits
+        assert_eq!(
+            Decimal::new(i64::MAX, 2).format(8),
+            "92233720368547758.07000000"
+        );
+        // arithmetic and rescaling saturate
+        let max = Decimal::new(i64::MAX, 0);
+        assert_eq!((max + Decimal::new(1, 0)).value, i64::MAX);
+        assert_eq!((-Decimal::new(i64::MIN, 0)).value, i64::MAX);
+        assert_eq!(max.rescale(2).value, i64::MAX);
+        assert_eq!(Decimal::new(i64::MIN, 0).rescale(2).value, i64::MIN);
+    }
+
+    #[test]
+    fn test_read_decimal_from_buffer() {
+        let col = decimal_col(8, 8);
+        let mut buf = [0u8; 2 * num_size()];
+        BigEndian::write_i64(&mut buf[0..8], 4_321_012_345_678);
+        BigEndian::write_i64(&mut buf[8..16], -1);
+        let ds = DataSource::new(&buf, num_size(), None);
+        assert_eq!(
+            col.format_decimal(ds.get_value_decimal(0, &col)).unwrap(),
+            "43210.12345678"
+        );
+        assert_eq!(
+            col.format_decimal(ds.get_value_decimal(1, &col)).unwrap(),
+            "-0.00000001"
+        );
+        assert_eq!(ds.get_value_f64(1, &col), Some(-0.00000001));
+        assert_eq!(ds.get_value_decimal(2, &col), None);
+    }
+
+    #[test]
+    fn test_decimal_suppress_zero() {
+        let mut col = decimal_col(4, 2);
+        col.suppress_zero = true;
+        assert_eq!(col.format_decimal(Some(Decimal::zero(4))).unwrap(), "");
+        assert_eq!(
+            col.format_decimal(Some(Decimal::new(1, 4))).unwrap(),
+            "0.00"
+        );
+    }
+}
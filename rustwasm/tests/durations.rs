@@ -0,0 +1,118 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::datefmt::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+
+    const NOW: f64 = 1_614_834_367_089.0;
+
+    fn fc(clock: &FixedClock) -> FormatContext<'_> {
+        FormatContext {
+            clock,
+            ..Default::default()
+        }
+    }
+
+    fn clock() -> FixedClock {
+        FixedClock {
+            now_ms: NOW,
+            offset: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+
+    fn compact(v: f64, unit: EpochUnit, precision: usize) -> String {
+        format_duration(v, unit, DurationStyle::Compact, precision)
+    }
+
+    #[test]
+    fn test_compact() {
+        assert_eq!(compact(1.25, EpochUnit::Millis, 2), "1.25ms");
+        assert_eq!(compact(1.5, EpochUnit::Millis, 0), "2ms");
+        assert_eq!(compact(3.0, EpochUnit::Seconds, 2), "3s");
+        assert_eq!(compact(850.0, EpochUnit::Nanos, 2), "850ns");
+        assert_eq!(compact(12_340.0, EpochUnit::Nanos, 1), "12.3µs");
+        assert_eq!(compact(0.9999999, EpochUnit::Seconds, 2), "1s");
+        assert_eq!(compact(125.0, EpochUnit::Seconds, 2), "2m 5s");
+        assert_eq!(compact(120.0, EpochUnit::Seconds, 2), "2m");
+        assert_eq!(compact(3_905_000.0, EpochUnit::Millis, 0), "1h 5m");
+        assert_eq!(compact(3_605.0, EpochUnit::Seconds, 0), "1h");
+        assert_eq!(compact(90_000.0, EpochUnit::Seconds, 0), "1d 1h");
+        assert_eq!(compact(-1.25, EpochUnit::Millis, 2), "-1.25ms");
+        assert_eq!(compact(0.0, EpochUnit::Millis, 2), "0ns");
+    }
+
+    #[test]
+    fn test_human() {
+        let human = |v: f64| format_duration(v, EpochUnit::Seconds, DurationStyle::Human, 2);
+        assert_eq!(human(0.25), "250 milliseconds");
+        assert_eq!(human(1.0), "1 second");
+        assert_eq!(human(61.0), "1 minute 1 second");
+        assert_eq!(human(7_200.0), "2 hours");
+    }
+
+    #[test]
+    fn test_relative() {
+        assert_eq!(
+            format_relative(NOW - 500.0, EpochUnit::Millis, NOW),
+            "just now"
+        );
+        assert_eq!(
+            format_relative(NOW - 3_200.0, EpochUnit::Millis, NOW),
+            "3s ago"
+        );
+        assert_eq!(
+            format_relative(NOW - 150_000.0, EpochUnit::Millis, NOW),
+            "2m ago"
+        );
+        assert_eq!(
+            format_relative(NOW / 1000.0 + 7_200.0, EpochUnit::Seconds, NOW),
+            "in 2h"
+        );
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut schema = Schema {
+            cols: vec![
+                Column {
+                    id: "latency".to_string(),
+                    col_type: ColumnType::Duration,
+                    size: num_size(),
+                    precision: 2,
+                    epoch_unit: EpochUnit::Micros,
+                    ..Default::default()
+                },
+                Column {
+                    id: "time".to_string(),
+                    col_type: ColumnType::Timestamp,
+                    size: num_size(),
+                    format: RELATIVE_FORMAT.to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        let c = clock();
+        assert_eq!(
+            schema.cols[0]
+                .format_value_with(Some(1_250.0), &fc(&c))
+                .unwrap(),
+            "1.25ms"
+        );
+        assert_eq!(
+            schema.cols[1]
+                .format_value_with(Some(NOW - 42_000.0), &fc(&c))
+                .unwrap(),
+            "42s ago"
+        );
+
+        schema.cols[0].format = "long".to_string();
+        let err = normalize_schema(&mut schema).unwrap_err();
+        assert_eq!(err.column.as_deref(), Some("latency"));
+    }
+}
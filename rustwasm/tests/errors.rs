@@ -0,0 +1,94 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::error::*;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::layout::*;
+    use rustwasm::grid::schema::*;
+
+    fn col(id: &str, col_type: ColumnType, size: usize) -> Column {
+        Column {
+            id: id.to_string(),
+            col_type,
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_reports_column_and_offset() {
+        let mut schema = Schema {
+            cols: vec![
+                col("symbol", ColumnType::String, 10),
+                col("price", ColumnType::Number, 4),
+            ],
+            ..Default::default()
+        };
+        let err = normalize_schema(&mut schema).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidSchema);
+        assert_eq!(err.column.as_deref(), Some("price"));
+        assert_eq!(err.offset, Some(10));
+        assert_eq!(
+            err.to_string(),
+            "InvalidSchema: numeric column size must be 8, got 4 (column 'price', offset 10)"
+        );
+    }
+
+    #[test]
+    fn test_normalize_rejects_empty_id() {
+        let mut schema = Schema {
+            cols: vec![col("", ColumnType::Number, 8)],
+            ..Default::default()
+        };
+        let err = normalize_schema(&mut schema).unwrap_err();
+        assert_eq!(err.column, None);
+        assert_eq!(err.offset, Some(0));
+    }
+
+    #[test]
+    fn test_normalize_rejects_duplicate_id() {
+        let mut schema = Schema {
+            cols: vec![
+                col("a", ColumnType::Number, 8),
+                col("a", ColumnType::Number, 8),
+            ],
+            ..Default::default()
+        };
+        let err = normalize_schema(&mut schema).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidSchema);
+        assert_eq!(err.column.as_deref(), Some("a"));
+        assert_eq!(err.offset, Some(8));
+    }
+
+    #[test]
+    fn test_normalize_twice_keeps_counts() {
+        let mut schema = Schema {
+            cols: vec![
+                col("a", ColumnType::Number, 8),
+                col("b", ColumnType::Number, 8),
+            ],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        normalize_schema(&mut schema).unwrap();
+        assert_eq!(schema.visible_col_count, 2);
+    }
+
+    #[test]
+    fn test_layout_error_conversion() {
+        let err = GridError::from(LayoutError::UnalignedBuffer {
+            len: 17,
+            data_width: 8,
+        });
+        assert_eq!(err.kind, ErrorKind::InvalidData);
+        assert_eq!(err.offset, Some(17));
+
+        let err = GridError::missing_column("cumSize");
+        assert_eq!(err.kind, ErrorKind::MissingColumn);
+        assert_eq!(
+            err.to_string(),
+            "MissingColumn: column 'cumSize' not found (column 'cumSize')"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::events::*;
+    use rustwasm::grid::selection::*;
+    use rustwasm::grid::state::*;
+    use serde_json::json;
+
+    fn json(event: &GridEvent) -> serde_json::Value {
+        serde_json::to_value(event).unwrap()
+    }
+
+    #[test]
+    fn test_payloads() {
+        let cell = CellEvent {
+            row: RowKey::Text("AAPL".to_string()),
+            col: "px".to_string(),
+            value: json!(1.5),
+            side: None,
+        };
+        assert_eq!(
+            json(&GridEvent::CellClick(cell.clone())),
+            json!({ "row": "AAPL", "col": "px", "value": 1.5 })
+        );
+        assert_eq!(
+            json(&GridEvent::CellDoubleClick(CellEvent {
+                side: Some("ask"),
+                ..cell
+            })),
+            json!({ "row": "AAPL", "col": "px", "value": 1.5, "side": "ask" })
+        );
+        assert_eq!(
+            json(&GridEvent::ScrollChange { top_index: 7 }),
+            json!({ "top_index": 7 })
+        );
+        let sort = GridEvent::SortChange {
+            sort: vec![SortKey {
+                col: "px".to_string(),
+                descending: true,
+            }],
+        };
+        assert_eq!(
+            json(&sort),
+            json!({ "sort": [{ "col": "px", "descending": true }] })
+        );
+        assert_eq!(sort.kind(), GridEventKind::SortChange);
+        assert_eq!(
+            GridEvent::SelectionChange(Selection::default()).kind(),
+            GridEventKind::SelectionChange
+        );
+    }
+
+    #[test]
+    fn test_cell_value() {
+        let col = Column {
+            id: "px".to_string(),
+            col_type: ColumnType::Number,
+            size: 8,
+            ..Default::default()
+        };
+        let mut buf = vec![0u8; 16];
+        BigEndian::write_f64(&mut buf[0..8], 1234.5);
+        BigEndian::write_f64(&mut buf[8..16], f64::NAN);
+        let ds = DataSource::new(&buf, 8, None);
+        assert_eq!(cell_value(&ds, 0, &col), json!(1234.5));
+        assert_eq!(cell_value(&ds, 1, &col), serde_json::Value::Null);
+
+        // decimals keep digits an f64 would lose
+        let col = Column {
+            col_type: ColumnType::Decimal,
+            scale: 2,
+            ..col
+        };
+        BigEndian::write_i64(&mut buf[0..8], 123_456_789_012_345_678);
+        let ds = DataSource::new(&buf, 8, None);
+        assert_eq!(cell_value(&ds, 0, &col), json!("1234567890123456.78"));
+    }
+
+    #[test]
+    fn test_toggle_sort() {
+        let mut query = ViewQuery::default();
+        let key = |col: &str, descending| SortKey {
+            col: col.to_string(),
+            descending,
+        };
+        query.toggle_sort("px");
+        assert_eq!(query.sort, vec![key("px", false)]);
+        query.toggle_sort("px");
+        assert_eq!(query.sort, vec![key("px", true)]);
+        query.toggle_sort("px");
+        assert!(query.sort.is_empty());
+        query.sort = vec![key("px", true), key("sym", false)];
+        query.toggle_sort("sym");
+        assert_eq!(query.sort, vec![key("sym", false)]);
+    }
+}
@@ -0,0 +1,116 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::export::*;
+    use rustwasm::grid::schema::*;
+
+    fn schema() -> Schema {
+        let mut schema = Schema {
+            cols: vec![
+                Column {
+                    id: "sym".to_string(),
+                    name: "Symbol".to_string(),
+                    col_type: ColumnType::String,
+                    size: 8,
+                    ..Default::default()
+                },
+                Column {
+                    id: "px".to_string(),
+                    name: "Price".to_string(),
+                    col_type: ColumnType::Number,
+                    size: 8,
+                    precision: 2,
+                    format: ",".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    fn data() -> Vec<u8> {
+        let mut buf = vec![0u8; 3 * 16];
+        for (row, (sym, px)) in [("A,B", 1234.5), ("C\"D", 2.0), ("E", 0.25)]
+            .iter()
+            .enumerate()
+        {
+            buf[row * 16..row * 16 + sym.len()].copy_from_slice(sym.as_bytes());
+            BigEndian::write_f64(&mut buf[row * 16 + 8..row * 16 + 16], *px);
+        }
+        buf
+    }
+
+    fn run(
+        schema: &Schema,
+        rows: std::ops::Range<usize>,
+        format: ExportFormat,
+        raw: bool,
+    ) -> (usize, String) {
+        let buf = data();
+        let ds = DataSource::new(&buf, 16, None);
+        let mut out = Vec::new();
+        let count = export_rows(
+            &mut out,
+            schema,
+            &ds,
+            rows,
+            &FormatContext::default(),
+            ExportOptions {
+                format,
+                raw,
+                header: true,
+            },
+        )
+        .unwrap();
+        (count, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            run(&schema(), 0..3, ExportFormat::Csv, false),
+            (
+                3,
+                "Symbol,Price\r\n\"A,B\",\"1,234.50\"\r\n\"C\"\"D\",2.00\r\nE,0.25\r\n".to_string()
+            )
+        );
+        // chunks after the first carry no header
+        assert_eq!(
+            run(&schema(), 2..10, ExportFormat::Csv, true),
+            (1, "E,0.25\r\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_json_lines() {
+        assert_eq!(
+            run(&schema(), 0..2, ExportFormat::JsonLines, true).1,
+            "{\"sym\":\"A,B\",\"px\":1234.5}\n{\"sym\":\"C\\\"D\",\"px\":2}\n"
+        );
+        assert_eq!(
+            run(&schema(), 0..1, ExportFormat::JsonLines, false).1,
+            "{\"sym\":\"A,B\",\"px\":\"1,234.50\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_hidden_and_order() {
+        let mut schema = schema();
+        schema.move_col("px", 0).unwrap();
+        assert_eq!(
+            run(&schema, 2..3, ExportFormat::JsonLines, true).1,
+            "{\"px\":0.25,\"sym\":\"E\"}\n"
+        );
+        schema.set_col_hidden("sym", true).unwrap();
+        assert_eq!(
+            run(&schema, 0..3, ExportFormat::Csv, true).1,
+            "Price\r\n1234.5\r\n2\r\n0.25\r\n"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::header::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+
+    fn col(id: &str, group: &[&str]) -> Column {
+        Column {
+            id: id.to_string(),
+            name: id.to_string(),
+            col_type: ColumnType::Number,
+            size: num_size(),
+            group: group.iter().map(|g| g.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn cell(text: &str, line: usize, lines: usize, first: usize, last: usize) -> HeaderCell<'_> {
+        HeaderCell {
+            text,
+            line,
+            lines,
+            first,
+            last,
+        }
+    }
+
+    #[test]
+    fn test_flat() {
+        let cols = [col("a", &[]), col("b", &[])];
+        let refs: Vec<&Column> = cols.iter().collect();
+        assert_eq!(header_lines(cols.iter()), 1);
+        assert_eq!(
+            header_cells(&refs, 1),
+            vec![cell("a", 0, 1, 0, 0), cell("b", 0, 1, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_groups() {
+        let cols = [
+            col("time", &[]),
+            col("bid_size", &["Book", "Bid"]),
+            col("bid_px", &["Book", "Bid"]),
+            col("ask_px", &["Book", "Ask"]),
+            col("last", &["Trades"]),
+        ];
+        let refs: Vec<&Column> = cols.iter().collect();
+        assert_eq!(header_lines(cols.iter()), 3);
+        assert_eq!(
+            header_cells(&refs, 3),
+            vec![
+                cell("Book", 0, 1, 1, 3),
+                cell("Trades", 0, 1, 4, 4),
+                cell("Bid", 1, 1, 1, 2),
+                cell("Ask", 1, 1, 3, 3),
+                cell("time", 0, 3, 0, 0), // spans the group lines
+                cell("bid_size", 2, 1, 1, 1),
+                cell("bid_px", 2, 1, 2, 2),
+                cell("ask_px", 2, 1, 3, 3),
+                cell("last", 1, 2, 4, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_header_lines() {
+        let mut schema = Schema {
+            cols: vec![col("a", &[]), col("b", &["Bid", "Top"]), col("c", &["Bid"])],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        assert_eq!(schema.header_lines, 3);
+        schema.set_col_hidden("b", true).unwrap();
+        assert_eq!(schema.header_lines, 2);
+
+        // moving a column out of its group splits the group cell
+        schema.set_col_hidden("b", false).unwrap();
+        schema.move_col("a", 1).unwrap();
+        let cols: Vec<&Column> = schema.get_visible_cols().collect();
+        let groups: Vec<_> = header_cells(&cols, schema.header_lines)
+            .into_iter()
+            .filter(|c| c.line == 0 && c.text == "Bid")
+            .map(|c| (c.first, c.last))
+            .collect();
+        assert_eq!(groups, vec![(0, 0), (2, 2)]);
+    }
+}
@@ -0,0 +1,139 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::layout::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+
+    fn schema(price_type: ColumnType) -> Schema {
+        let mut schema = Schema {
+            cols: vec![
+                Column {
+                    id: "symbol".to_string(),
+                    col_type: ColumnType::String,
+                    size: 10,
+                    ..Default::default()
+                },
+                Column {
+                    id: "price".to_string(),
+                    col_type: price_type,
+                    size: num_size(),
+                    ..Default::default()
+                },
+            ],
+            buffer_header: true,
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    fn buffer(schema: &Schema, rows: usize, capacity: usize) -> Vec<u8> {
+        let mut buf = vec![0u8; HEADER_SIZE + capacity * schema.data_width];
+        BufferHeader::new(schema, rows).write(&mut buf);
+        buf
+    }
+
+    #[test]
+    fn test_schema_hash() {
+        let a = schema(ColumnType::Number);
+        assert_eq!(a.data_width, 18);
+        assert_eq!(a.layout_hash, schema_hash(&a));
+        assert_eq!(a.layout_hash, schema(ColumnType::Number).layout_hash);
+        assert_ne!(a.layout_hash, schema(ColumnType::Decimal).layout_hash);
+    }
+
+    #[test]
+    fn test_header_round_trip() {
+        let schema = schema(ColumnType::Number);
+        let buf = buffer(&schema, 3, 3);
+        let header = BufferHeader::read(&buf).unwrap();
+        assert_eq!(header, BufferHeader::new(&schema, 3));
+        assert_eq!(header.magic, HEADER_MAGIC);
+    }
+
+    #[test]
+    fn test_validate_strips_header_and_unused_rows() {
+        let schema = schema(ColumnType::Number);
+        let buf = buffer(&schema, 2, 5);
+        let payload = validate_layout(&schema, &buf, schema.data_width).unwrap();
+        assert_eq!(payload.len(), 2 * schema.data_width);
+    }
+
+    #[test]
+    fn test_validate_errors() {
+        let schema = schema(ColumnType::Number);
+        let w = schema.data_width;
+
+        let other = buffer(&self::schema(ColumnType::Decimal), 1, 1);
+        let err = validate_layout(&schema, &other, w).unwrap_err();
+        assert!(matches!(err, LayoutError::SchemaMismatch { .. }));
+        assert!(err.to_string().contains("schemas differ"));
+
+        let mut bad = buffer(&schema, 1, 1);
+        bad[0] = 0;
+        assert!(matches!(
+            validate_layout(&schema, &bad, w),
+            Err(LayoutError::BadMagic(_))
+        ));
+
+        let short = buffer(&schema, 4, 1);
+        assert_eq!(
+            validate_layout(&schema, &short, w),
+            Err(LayoutError::BufferTooSmall {
+                len: HEADER_SIZE + w,
+                expected: HEADER_SIZE + 4 * w
+            })
+        );
+
+        let ok = buffer(&schema, 1, 1);
+        assert_eq!(
+            validate_layout(&schema, &ok, w - 1),
+            Err(LayoutError::DataWidthMismatch {
+                found: w - 1,
+                expected: w
+            })
+        );
+        assert_eq!(
+            validate_layout(&schema, &ok, 0),
+            Err(LayoutError::ZeroDataWidth)
+        );
+        assert!(matches!(
+            validate_layout(&schema, &ok[..3], w),
+            Err(LayoutError::BufferTooSmall { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_row_count_overflow() {
+        let mut schema = schema(ColumnType::Number);
+        schema.data_width = usize::MAX / 2;
+        let buf = buffer(&self::schema(ColumnType::Number), u32::MAX as usize, 1);
+        assert_eq!(
+            validate_layout(&schema, &buf, schema.data_width),
+            Err(LayoutError::RowCountOverflow {
+                row_count: u32::MAX,
+                data_width: usize::MAX / 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_without_header() {
+        let mut schema = schema(ColumnType::Number);
+        schema.buffer_header = false;
+        let buf = vec![0u8; 2 * schema.data_width + 1];
+        assert!(matches!(
+            validate_layout(&schema, &buf, schema.data_width),
+            Err(LayoutError::UnalignedBuffer { .. })
+        ));
+        assert_eq!(
+            validate_layout(&schema, &buf[1..], schema.data_width)
+                .unwrap()
+                .len(),
+            buf.len() - 1
+        );
+    }
+}
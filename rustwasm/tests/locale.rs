@@ -0,0 +1,127 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use chrono::FixedOffset;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::datefmt::*;
+    use rustwasm::grid::decimal::*;
+    use rustwasm::grid::locale::*;
+    use rustwasm::grid::numfmt::*;
+    use rustwasm::grid::price::*;
+    use rustwasm::grid::renderer::*;
+
+    // 2021-03-04 17:06:07 UTC, a Thursday
+    const MS: f64 = 1_614_877_567_000.0;
+
+    fn fc(locale: &Locale) -> FormatContext<'_> {
+        FormatContext {
+            locale,
+            ..Default::default()
+        }
+    }
+
+    fn col(col_type: ColumnType, format: &str) -> Column {
+        Column {
+            col_type,
+            size: num_size(),
+            precision: 2,
+            format: format.to_string(),
+            timezone: "UTC".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(Locale::find("").unwrap(), EN_US);
+        assert_eq!(Locale::find("de-DE").unwrap(), DE_DE);
+        assert_eq!(Locale::find("de_ch").unwrap(), DE_CH);
+        assert_eq!(Locale::find("fr").unwrap(), FR_FR);
+        assert_eq!(Locale::find("es-MX").unwrap(), ES_ES);
+        assert!(Locale::find("xx-YY").is_err());
+    }
+
+    #[test]
+    fn test_numbers() {
+        let nf = NumberFormat::parse(",.2f").unwrap();
+        assert_eq!(nf.format_f64(1234.56, 0, "", "", &EN_US), "1,234.56");
+        assert_eq!(nf.format_f64(1234.56, 0, "", "", &DE_DE), "1.234,56");
+        assert_eq!(nf.format_f64(1234.56, 0, "", "", &DE_CH), "1’234.56");
+        assert_eq!(
+            nf.format_f64(-1234567.5, 0, "", " €", &FR_FR),
+            "-1\u{202f}234\u{202f}567,50 €"
+        );
+        assert_eq!(
+            nf.format_decimal(Decimal::new(123456789, 3), 0, "", "", &IT_IT),
+            "123.456,79"
+        );
+        let col = col(ColumnType::Number, ",.1%");
+        assert_eq!(
+            col.format_value_with(Some(0.1234), &fc(&ES_ES)).unwrap(),
+            "12,3%"
+        );
+    }
+
+    #[test]
+    fn test_prices_and_durations() {
+        let mut price = col(ColumnType::Number, "");
+        price.price = Some(PriceFormat {
+            tick_size: 0.25,
+            big_figure_digits: 2,
+            ..Default::default()
+        });
+        assert_eq!(
+            price.format_value_with(Some(99.5), &fc(&DE_DE)).unwrap(),
+            "99,50"
+        );
+        let parts = price
+            .format_price_parts_with(Some(99.5), &fc(&DE_DE))
+            .unwrap();
+        assert_eq!(parts.to_text(), "99,50");
+
+        let mut duration = col(ColumnType::Duration, "");
+        duration.epoch_unit = EpochUnit::Micros;
+        assert_eq!(
+            duration
+                .format_value_with(Some(1250.0), &fc(&DE_DE))
+                .unwrap(),
+            "1,25ms"
+        );
+    }
+
+    #[test]
+    fn test_date_names() {
+        let fmt = |locale: &Locale, format: &str| {
+            col(ColumnType::DateTime, format)
+                .format_value_with(Some(MS), &fc(locale))
+                .unwrap()
+        };
+        assert_eq!(fmt(&EN_US, "%A %e %B %Y"), "Thursday  4 March 2021");
+        assert_eq!(fmt(&DE_DE, "%A, %d. %B %Y"), "Donnerstag, 04. März 2021");
+        assert_eq!(fmt(&FR_FR, "%a %d %b"), "jeu. 04 mars");
+        assert_eq!(fmt(&ES_ES, "%d %b %Y %H:%M"), "04 mar 2021 17:06");
+        assert_eq!(fmt(&EN_US, "%I:%M %p"), "05:06 PM");
+        assert_eq!(fmt(&EN_US, "%I:%M %P"), "05:06 pm");
+        // composite specifiers are localized too
+        assert_eq!(fmt(&IT_IT, "%c"), "gio mar  4 17:06:07 2021");
+
+        let local = FixedClock {
+            now_ms: MS,
+            offset: FixedOffset::east_opt(3600).unwrap(),
+        };
+        let mut col = col(ColumnType::DateTime, "%d %B %H:%M");
+        col.timezone = String::new();
+        assert_eq!(
+            col.format_value_with(
+                Some(MS),
+                &FormatContext {
+                    locale: &PT_BR,
+                    clock: &local
+                }
+            )
+            .unwrap(),
+            "04 março 18:06"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::measure::*;
+    use std::borrow::Cow;
+
+    // every char is 10px wide
+    fn width(text: &str) -> f64 {
+        text.chars().count() as f64 * 10.0
+    }
+
+    fn fit(text: &str, max_width: f64, numeric: bool) -> Cow<'_, str> {
+        fit_text(text, max_width, numeric, &mut width)
+    }
+
+    #[test]
+    fn test_fits() {
+        assert!(matches!(fit("hello", 50.0, false), Cow::Borrowed("hello")));
+        assert_eq!(fit("", -1.0, false), "");
+        assert_eq!(fit("1,234.50", 80.0, true), "1,234.50");
+    }
+
+    #[test]
+    fn test_ellipsis() {
+        assert_eq!(fit("hello world", 60.0, false), "hello…");
+        assert_eq!(fit("hello world", 70.0, false), "hello…"); // no "hello …"
+        assert_eq!(fit("hello world", 100.0, false), "hello wor…");
+        assert_eq!(fit("Zürich Börse", 40.0, false), "Zür…");
+        assert_eq!(fit("hello", 15.0, false), "…");
+        assert_eq!(fit("hello", 5.0, false), "");
+    }
+
+    #[test]
+    fn test_numeric_overflow() {
+        assert_eq!(fit("1,234,567.89", 80.0, true), NUMERIC_OVERFLOW);
+        assert_eq!(fit("1,234,567.89", 20.0, true), "");
+    }
+
+    #[test]
+    fn test_cache() {
+        let mut cache = MeasureCache::new();
+        let mut calls = 0;
+        let mut measure = |font: &str, text: &str| {
+            cache.measure(font, text, |t| {
+                calls += 1;
+                width(t)
+            })
+        };
+        assert_eq!(measure("16px sans-serif", "abc"), 30.0);
+        assert_eq!(measure("16px sans-serif", "abc"), 30.0);
+        assert_eq!(measure("bold 16px sans-serif", "abc"), 30.0);
+        assert_eq!(calls, 2);
+        assert_eq!(cache.len(), 2);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cache_eviction() {
+        let mut cache = MeasureCache::new();
+        let mut calls = 0;
+        let font = "16px sans-serif";
+        for i in 0..3 * GENERATION_SIZE {
+            cache.measure(font, &i.to_string(), width);
+            // a width in use every frame is never measured again
+            cache.measure(font, "hot", |t| {
+                calls += 1;
+                width(t)
+            });
+        }
+        assert_eq!(calls, 1);
+        assert!(cache.len() <= 2 * GENERATION_SIZE);
+        // old values were dropped
+        let mut measured = false;
+        cache.measure(font, "0", |t| {
+            measured = true;
+            width(t)
+        });
+        assert!(measured);
+    }
+}
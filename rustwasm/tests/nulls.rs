@@ -0,0 +1,101 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+
+    fn schema(null_mode: NullMode) -> Schema {
+        let mut schema = Schema {
+            cols: vec![
+                Column {
+                    id: "price".to_string(),
+                    col_type: ColumnType::Number,
+                    size: num_size(),
+                    ..Default::default()
+                },
+                Column {
+                    id: "qty".to_string(),
+                    col_type: ColumnType::Decimal,
+                    size: num_size(),
+                    scale: 2,
+                    ..Default::default()
+                },
+            ],
+            null_mode,
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    #[test]
+    fn test_bitmap_offsets() {
+        let schema = schema(NullMode::Bitmap);
+        assert_eq!(schema.validity_size, 1);
+        assert_eq!(schema.cols[0].data_offset, 1);
+        assert_eq!(schema.cols[1].data_offset, 9);
+        assert_eq!(schema.cols[1].validity_index, 1);
+        assert_eq!(validity_size(8), 1);
+        assert_eq!(validity_size(9), 2);
+    }
+
+    #[test]
+    fn test_bitmap_nulls() {
+        let schema = schema(NullMode::Bitmap);
+        let (price, qty) = (&schema.cols[0], &schema.cols[1]);
+        let width = 17;
+        let mut buf = vec![0u8; 2 * width];
+        // row 0: price valid, qty null
+        buf[0] = 0b01;
+        BigEndian::write_f64(&mut buf[1..9], 1.5);
+        // row 1: price null, qty valid
+        buf[width] = 0b10;
+        BigEndian::write_i64(&mut buf[width + 9..width + 17], 250);
+
+        let ds = DataSource::new(&buf, width, None);
+        assert_eq!(ds.get_value_f64(0, price), Some(1.5));
+        assert!(ds.is_null(0, qty));
+        assert_eq!(ds.get_value_decimal(0, qty), None);
+        assert!(ds.is_null(1, price));
+        assert_eq!(ds.get_value_f64(1, price), None);
+        assert_eq!(ds.get_value_decimal(1, qty).unwrap().format(2), "2.50");
+        // out of range rows are not nulls
+        assert!(!ds.is_null(2, price));
+    }
+
+    #[test]
+    fn test_sentinel_nulls_skipped_by_aggregations() {
+        let schema = schema(NullMode::Sentinel);
+        let (price, qty) = (&schema.cols[0], &schema.cols[1]);
+        let width = 16;
+        let mut buf = vec![0u8; 3 * width];
+        BigEndian::write_f64(&mut buf[0..8], 3.0);
+        BigEndian::write_i64(&mut buf[8..16], 100);
+        BigEndian::write_f64(&mut buf[16..24], 7.0);
+        BigEndian::write_i64(&mut buf[24..32], DECIMAL_NULL);
+        BigEndian::write_f64(&mut buf[32..40], f64::NAN);
+        BigEndian::write_i64(&mut buf[40..48], 5);
+
+        let ds = DataSource::new(&buf, width, None);
+        assert!(ds.is_null(2, price));
+        assert!(ds.is_null(1, qty));
+        assert_eq!(ds.values_f64(price).collect::<Vec<_>>(), [3.0, 7.0]);
+        assert_eq!(ds.max_f64(price), Some(7.0));
+        assert_eq!(ds.sum_decimal(qty).format(2), "1.05");
+    }
+
+    #[test]
+    fn test_no_null_mode() {
+        let schema = schema(NullMode::None);
+        let price = &schema.cols[0];
+        let mut buf = vec![0u8; 16];
+        BigEndian::write_f64(&mut buf[0..8], f64::NAN);
+        let ds = DataSource::new(&buf, 16, None);
+        assert!(!ds.is_null(0, price));
+        assert!(ds.get_value_f64(0, price).unwrap().is_nan());
+    }
+}
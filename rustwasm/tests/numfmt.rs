@@ -0,0 +1,143 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::decimal::*;
+    use rustwasm::grid::locale::*;
+    use rustwasm::grid::numfmt::*;
+
+    fn fmt(spec: &str, v: f64) -> String {
+        NumberFormat::parse(spec)
+            .unwrap()
+            .format_f64(v, 2, "", "", &EN_US)
+    }
+
+    #[test]
+    fn test_parse() {
+        let nf = NumberFormat::parse("(,.3%").unwrap();
+        assert_eq!(nf.sign, Sign::Parens);
+        assert!(nf.grouping);
+        assert_eq!(nf.precision, Some(3));
+        assert_eq!(nf.kind, FormatKind::Percent);
+        assert_eq!(NumberFormat::parse("").unwrap(), NumberFormat::DEFAULT);
+        assert!(NumberFormat::parse(".x").is_err());
+        assert!(NumberFormat::parse(",.2q").is_err());
+        assert!(NumberFormat::parse(".0g").is_err());
+    }
+
+    #[test]
+    fn test_fixed_and_grouping() {
+        assert_eq!(fmt("", 1234.5), "1234.50");
+        assert_eq!(fmt(",.2f", 1234567.891), "1,234,567.89");
+        assert_eq!(fmt(",.0f", -999.5), "-1,000");
+        assert_eq!(fmt(",", 12.0), "12.00");
+        assert_eq!(fmt(".2f", -0.001), "0.00");
+    }
+
+    #[test]
+    fn test_signs() {
+        assert_eq!(fmt("+.1f", 2.25), "+2.2");
+        assert_eq!(fmt("+.1f", -2.0), "-2.0");
+        assert_eq!(fmt("(,.2f", -1234.5), "(1,234.50)");
+        assert_eq!(fmt("(,.2f", 1234.5), "1,234.50");
+    }
+
+    #[test]
+    fn test_percent_and_basis_points() {
+        assert_eq!(fmt(".1%", 0.1234), "12.3%");
+        assert_eq!(fmt("+.0b", 0.00126), "+13bp");
+        assert_eq!(fmt(".1b", -0.0001), "-1.0bp");
+    }
+
+    #[test]
+    fn test_abbreviations() {
+        assert_eq!(fmt(".1a", 1_234_567.0), "1.2M");
+        assert_eq!(fmt(".2a", -3_450_000_000.0), "-3.45B");
+        assert_eq!(fmt(".0a", 950.0), "950");
+        assert_eq!(fmt(".1a", 0.0), "0.0");
+        assert_eq!(fmt(".1a", 12_000.0), "12.0K");
+    }
+
+    #[test]
+    fn test_significant_digits() {
+        assert_eq!(fmt(".3g", 0.00123456), "0.00123");
+        assert_eq!(fmt(".3g", 123456.0), "123000");
+        assert_eq!(fmt(",.4g", 123456.0), "123,500");
+        assert_eq!(fmt(".2g", -1.234), "-1.2");
+    }
+
+    #[test]
+    fn test_affixes() {
+        let nf = NumberFormat::parse("(,.2f").unwrap();
+        assert_eq!(
+            nf.format_f64(-1234.5, 0, "$", " USD", &EN_US),
+            "($1,234.50 USD)"
+        );
+        let nf = NumberFormat::parse("+.1a").unwrap();
+        assert_eq!(nf.format_f64(-2_500_000.0, 0, "€", "", &EN_US), "-€2.5M");
+    }
+
+    #[test]
+    fn test_decimal_exact() {
+        let nf = NumberFormat::parse(",.8f").unwrap();
+        let v = Decimal::new(123_456_789_012_345_678, 8);
+        assert_eq!(
+            nf.format_decimal(v, 0, "", "", &EN_US),
+            "1,234,567,890.12345678"
+        );
+        let nf = NumberFormat::parse(".2%").unwrap();
+        assert_eq!(
+            nf.format_decimal(Decimal::new(1234, 4), 0, "", "", &EN_US),
+            "12.34%"
+        );
+        let nf = NumberFormat::parse(".3a").unwrap();
+        assert_eq!(
+            nf.format_decimal(Decimal::new(-1_234_567, 0), 0, "", "", &EN_US),
+            "-1.235M"
+        );
+    }
+
+    #[test]
+    fn test_decimal_shift_overflow() {
+        // i64::MAX * 100 doesn't fit, falls back to f64
+        let nf = NumberFormat::parse(".0%").unwrap();
+        assert_eq!(
+            nf.format_decimal(Decimal::new(i64::MAX, 0), 0, "", "", &EN_US),
+            "922337203685477580800%"
+        );
+        let nf = NumberFormat::parse(".0b").unwrap();
+        assert_eq!(
+            nf.format_decimal(Decimal::new(-i64::MAX, 2), 0, "", "", &EN_US),
+            "-922337203685477580800bp"
+        );
+    }
+
+    #[test]
+    fn test_column_format_value() {
+        let col = Column {
+            col_type: ColumnType::Number,
+            format: ",.1f".to_string(),
+            prefix: "$".to_string(),
+            precision: 4,
+            ..Default::default()
+        };
+        assert_eq!(col.format_value(Some(12345.67)).unwrap(), "$12,345.7");
+        // precision is used when the spec has none
+        let col = Column {
+            col_type: ColumnType::Number,
+            format: ",".to_string(),
+            precision: 3,
+            ..Default::default()
+        };
+        assert_eq!(col.format_value(Some(1234.5)).unwrap(), "1,234.500");
+    }
+
+    #[test]
+    fn test_group() {
+        assert_eq!(group("1", ','), "1");
+        assert_eq!(group("123", ','), "123");
+        assert_eq!(group("1234", ','), "1,234");
+        assert_eq!(group("123456", '.'), "123.456");
+    }
+}
@@ -0,0 +1,138 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::pointer::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+
+    // three 100px columns under a 30px header
+    fn layout() -> HeaderLayout {
+        HeaderLayout {
+            top: 0.0,
+            bottom: 30.0,
+            edges: vec![0.0, 100.0, 200.0, 300.0],
+            ids: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            row_height: 30.0,
+        }
+    }
+
+    fn schema(ids: &[&str]) -> Schema {
+        let mut schema = Schema {
+            cols: ids
+                .iter()
+                .map(|id| Column {
+                    id: id.to_string(),
+                    col_type: ColumnType::Number,
+                    size: num_size(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    #[test]
+    fn test_hit() {
+        let layout = layout();
+        assert_eq!(layout.hit(50.0, 10.0), Some(HeaderHit::Cell(0)));
+        assert_eq!(layout.hit(102.0, 10.0), Some(HeaderHit::Border(0)));
+        assert_eq!(layout.hit(298.0, 10.0), Some(HeaderHit::Border(2)));
+        assert_eq!(layout.hit(2.0, 10.0), Some(HeaderHit::Cell(0)));
+        assert_eq!(layout.hit(50.0, 40.0), None); // data row
+        assert_eq!(layout.slot(40.0), 0);
+        assert_eq!(layout.slot(160.0), 2);
+        assert_eq!(layout.slot(290.0), 3);
+    }
+
+    #[test]
+    fn test_resize() {
+        let layout = layout();
+        let mut pointer = PointerState::default();
+        assert!(pointer.down(&layout, 199.0, 10.0));
+        assert_eq!(pointer.cursor(&layout, 0.0, 100.0), "col-resize");
+        assert_eq!(
+            pointer.move_to(&layout, 230.0),
+            PointerAction::Resize {
+                id: "b".to_string(),
+                width: 131.0
+            }
+        );
+        assert_eq!(pointer.guide(&layout), Some(Guide::Resize(231.0)));
+        // can't shrink below the minimum
+        assert_eq!(
+            pointer.up(&layout, 0.0),
+            PointerAction::Resize {
+                id: "b".to_string(),
+                width: MIN_RESIZE_WIDTH
+            }
+        );
+        assert_eq!(pointer.guide(&layout), None);
+    }
+
+    #[test]
+    fn test_resize_after_layout_shrinks() {
+        let mut pointer = PointerState::default();
+        assert!(pointer.down(&layout(), 298.0, 10.0));
+        // the host hid columns before the next move
+        let narrow = HeaderLayout {
+            edges: vec![0.0, 100.0],
+            ids: vec!["a".to_string()],
+            ..layout()
+        };
+        assert_eq!(pointer.move_to(&narrow, 320.0), PointerAction::None);
+        assert_eq!(pointer.up(&narrow, 320.0), PointerAction::None);
+        assert_eq!(pointer.drag, Drag::Idle);
+    }
+
+    #[test]
+    fn test_move() {
+        let layout = layout();
+        let mut pointer = PointerState::default();
+        assert!(pointer.down(&layout, 50.0, 10.0));
+        assert_eq!(pointer.move_to(&layout, 52.0), PointerAction::None); // under the threshold
+        assert_eq!(pointer.move_to(&layout, 260.0), PointerAction::Redraw);
+        assert_eq!(pointer.guide(&layout), Some(Guide::Drop(300.0)));
+        assert_eq!(
+            pointer.up(&layout, 260.0),
+            PointerAction::Move {
+                id: "a".to_string(),
+                slot: 3
+            }
+        );
+
+        // a click is reported, a drop back in place changes nothing
+        assert!(pointer.down(&layout, 150.0, 10.0));
+        assert_eq!(
+            pointer.up(&layout, 150.0),
+            PointerAction::Click {
+                id: "b".to_string()
+            }
+        );
+        assert!(pointer.down(&layout, 150.0, 10.0));
+        assert_eq!(pointer.move_to(&layout, 190.0), PointerAction::Redraw);
+        assert_eq!(pointer.up(&layout, 190.0), PointerAction::Redraw);
+        assert!(!pointer.down(&layout, 150.0, 50.0));
+    }
+
+    #[test]
+    fn test_drop_position() {
+        let mut schema = schema(&["a", "h", "b", "c"]);
+        schema.set_col_hidden("h", true).unwrap();
+        let mut drop = |id: &str, slot: usize| {
+            let to = drop_position(&schema, id, slot).unwrap();
+            schema.move_col(id, to).unwrap();
+            schema
+                .get_display_cols()
+                .map(|c| c.id.clone())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        assert_eq!(drop("a", 3), "h,b,c,a");
+        assert_eq!(drop("a", 0), "h,a,b,c"); // in front of b
+        assert_eq!(drop("c", 1), "h,a,c,b");
+    }
+}
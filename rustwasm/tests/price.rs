@@ -0,0 +1,154 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::decimal::*;
+    use rustwasm::grid::price::*;
+
+    fn fraction(fraction: u32, sub_fraction: u32) -> PriceFormat {
+        PriceFormat {
+            fraction,
+            sub_fraction,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tick_rounding() {
+        let pf = PriceFormat {
+            tick_size: 0.25,
+            ..Default::default()
+        };
+        assert_eq!(pf.tick_precision(), 2);
+        assert_eq!(pf.format(4321.13, 0), "4321.25");
+        assert_eq!(pf.format(4321.12, 0), "4321.00");
+        let pf = PriceFormat {
+            tick_size: 0.0005,
+            ..Default::default()
+        };
+        assert_eq!(pf.format(1.23456, 0), "1.2345");
+        assert_eq!(pf.format(1.23456, 5), "1.23450");
+    }
+
+    #[test]
+    fn test_32nds() {
+        let pf = fraction(32, 2);
+        assert_eq!(pf.format(99.5, 0), "99-16");
+        assert_eq!(pf.format(99.515625, 0), "99-16+");
+        assert_eq!(pf.format(101.03125, 0), "101-01");
+        assert_eq!(pf.format(-0.5, 0), "-0-16");
+        assert_eq!(fraction(32, 0).format(99.515625, 0), "99-17");
+    }
+
+    #[test]
+    fn test_quarter_32nds_and_64ths() {
+        let pf = fraction(32, 4);
+        assert_eq!(pf.format(99.5078125, 0), "99-162");
+        assert_eq!(pf.format(99.5234375, 0), "99-166");
+        assert_eq!(pf.format(99.5, 0), "99-160");
+        assert_eq!(fraction(64, 0).format(99.515625, 0), "99-33");
+        assert_eq!(fraction(8, 0).format(12.375, 0), "12-3");
+    }
+
+    #[test]
+    fn test_big_figure_split() {
+        // EURUSD: 1.23 big figure, 45 pips, 6 pipette
+        let pf = PriceFormat {
+            big_figure_digits: 2,
+            trailing_digits: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            pf.format_parts(1.23456, 5),
+            PriceParts {
+                lead: "1.23".to_string(),
+                big_figure: "45".to_string(),
+                tail: "6".to_string(),
+            }
+        );
+        let pf = PriceFormat {
+            fraction: 32,
+            sub_fraction: 2,
+            big_figure_digits: 3,
+            ..Default::default()
+        };
+        let parts = pf.format_parts(99.515625, 0);
+        assert_eq!(parts.lead, "99-");
+        assert_eq!(parts.big_figure, "16+");
+        assert_eq!(parts.to_text(), "99-16+");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(fraction(32, 2).validate().is_ok());
+        assert!(fraction(30, 0).validate().is_err());
+        assert!(fraction(32, 3).validate().is_err());
+        assert!(fraction(0, 2).validate().is_err());
+        let pf = PriceFormat {
+            tick_size: -1.0,
+            ..Default::default()
+        };
+        assert!(pf.validate().is_err());
+    }
+
+    #[test]
+    fn test_column_with_price() {
+        let col = Column {
+            col_type: ColumnType::Number,
+            price: Some(PriceFormat {
+                tick_size: 0.5,
+                big_figure_digits: 2,
+                ..Default::default()
+            }),
+            prefix: "$".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(col.format_value(Some(101.3)).unwrap(), "$101.5");
+        let parts = col.format_price_parts(Some(101.3)).unwrap();
+        assert_eq!(parts.lead, "$10");
+        assert_eq!(parts.big_figure, "1.5");
+    }
+
+    #[test]
+    fn test_decimal_price() {
+        let pf = PriceFormat {
+            tick_size: 0.00005,
+            big_figure_digits: 2,
+            trailing_digits: 1,
+            ..Default::default()
+        };
+        // 1234.56789012345678 would lose digits as f64
+        let parts = pf.format_decimal_parts(Decimal::new(123_456_789_012_345_678, 14), 0);
+        assert_eq!(parts.to_text(), "1234.56790");
+        assert_eq!(parts.big_figure, "79");
+        assert_eq!(
+            pf.format_decimal(Decimal::new(-12_345_675, 4), 0),
+            "-1234.56750"
+        );
+        assert_eq!(
+            fraction(32, 2).format_decimal(Decimal::new(99_515_625, 6), 0),
+            "99-16+"
+        );
+
+        let col = Column {
+            col_type: ColumnType::Decimal,
+            scale: 4,
+            price: Some(pf),
+            ..Default::default()
+        };
+        assert_eq!(
+            col.format_decimal(Some(Decimal::new(12_345_675, 4)))
+                .unwrap(),
+            "1234.56750"
+        );
+        let parts = col
+            .format_decimal_price_parts_with(
+                Some(Decimal::new(12_345_675, 4)),
+                &FormatContext::default(),
+            )
+            .unwrap();
+        assert_eq!(parts.big_figure, "75");
+        assert_eq!(parts.tail, "0");
+    }
+}
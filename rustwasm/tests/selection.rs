@@ -0,0 +1,180 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+    use rustwasm::grid::selection::*;
+    use std::ops::Range;
+
+    fn key(id: &str) -> RowKey {
+        RowKey::Text(id.to_string())
+    }
+
+    fn cell(row: &str, col: &str) -> CellRef {
+        CellRef {
+            row: key(row),
+            col: col.to_string(),
+        }
+    }
+
+    // rows keyed by order id, columns a b c
+    fn resolve_in(
+        selection: &Selection,
+        rows: &[&str],
+        window: Range<usize>,
+        last: &ResolvedSelection,
+    ) -> ResolvedSelection {
+        selection.resolve(
+            window,
+            last,
+            &|row| rows.get(row).map(|id| key(id)),
+            &|id| ["a", "b", "c"].iter().position(|c| *c == id),
+        )
+    }
+
+    fn resolve(selection: &Selection, rows: &[&str]) -> ResolvedSelection {
+        resolve_in(
+            selection,
+            rows,
+            0..rows.len(),
+            &ResolvedSelection::default(),
+        )
+    }
+
+    #[test]
+    fn test_click() {
+        let mut selection = Selection::default();
+        selection.click(cell("o1", "a"), false, false);
+        selection.click(cell("o3", "b"), true, false);
+        let resolved = resolve(&selection, &["o1", "o2", "o3", "o4"]);
+        assert_eq!(resolved.cursor, Some((2, 1)));
+        assert_eq!(resolved.range, Some(((0, 2), (0, 1))));
+        assert!(resolved.contains(1, 1));
+        assert!(!resolved.contains(3, 0));
+
+        selection.click(cell("o4", "c"), false, true);
+        let resolved = resolve(&selection, &["o1", "o2", "o3", "o4"]);
+        assert!(resolved.contains(3, 0)); // whole row
+        assert_eq!(resolved.range, Some(((3, 3), (2, 2))));
+        selection.click(cell("o4", "c"), false, true);
+        assert!(selection.rows.is_empty());
+    }
+
+    #[test]
+    fn test_survives_updates() {
+        let mut selection = Selection::default();
+        selection.click(cell("o2", "a"), false, false);
+        selection.click(cell("o3", "a"), true, false);
+        // a new order on top shifts the buffer rows
+        let resolved = resolve(&selection, &["o9", "o1", "o2", "o3"]);
+        assert_eq!(resolved.range, Some(((2, 3), (0, 0))));
+        // o2 is gone, the range collapses to the cursor
+        let resolved = resolve(&selection, &["o1", "o3"]);
+        assert_eq!(resolved.range, Some(((1, 1), (0, 0))));
+        // and so does the selection without its cursor row
+        assert!(resolve(&selection, &["o1"]).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_window() {
+        let ids: Vec<String> = (0..100).map(|i| format!("o{}", i)).collect();
+        let rows: Vec<&str> = ids.iter().map(|id| id.as_str()).collect();
+        let mut selection = Selection::default();
+        selection.click(cell("o5", "a"), false, false);
+        selection.click(cell("o50", "b"), true, false);
+        selection.click(cell("o45", "b"), true, true);
+        let all = resolve(&selection, &rows);
+        assert_eq!(all.range, Some(((5, 45), (0, 1))));
+        assert_eq!(all.rows, [45]);
+
+        // the anchor is above the window, found at its last row
+        let visible = resolve_in(&selection, &rows, 40..60, &all);
+        assert_eq!(visible, all);
+        let visible = resolve_in(&selection, &rows, 40..60, &ResolvedSelection::default());
+        assert_eq!(visible.range, Some(((45, 45), (1, 1))));
+        // selected rows outside the window are skipped
+        let visible = resolve_in(&selection, &rows, 0..10, &all);
+        assert_eq!(visible.range, all.range);
+        assert!(visible.rows.is_empty());
+    }
+
+    #[test]
+    fn test_row_key_hash() {
+        let keys: std::collections::HashSet<RowKey> =
+            vec![RowKey::Number(0.0), RowKey::Number(-0.0), key("0")]
+                .into_iter()
+                .collect();
+        assert_eq!(keys.len(), 2);
+    }
+
+    #[test]
+    fn test_navigate() {
+        let rows: Vec<String> = (0..100).map(|i| format!("o{}", i)).collect();
+        let row_key = |row: usize| rows.get(row).map(|id| key(id));
+        let col_id = |i: usize| ["a", "b", "c"].get(i).map(|c| c.to_string());
+        let mut selection = Selection::default();
+        let nav = |selection: &mut Selection, nav: Nav, shift: bool| {
+            let refs: Vec<&str> = rows.iter().map(|r| r.as_str()).collect();
+            let resolved = resolve(selection, &refs);
+            selection.navigate(nav, shift, &resolved, rows.len(), 3, 10, &row_key, &col_id)
+        };
+        // nothing selected starts at the top left
+        assert_eq!(nav(&mut selection, Nav::Down, false), Some(0));
+        assert_eq!(selection.cursor, Some(cell("o0", "a")));
+        assert_eq!(nav(&mut selection, Nav::PageDown, false), Some(10));
+        assert_eq!(nav(&mut selection, Nav::End, true), Some(10));
+        assert_eq!(selection.cursor, Some(cell("o10", "c")));
+        assert_eq!(selection.anchor, Some(cell("o10", "a")));
+        assert_eq!(nav(&mut selection, Nav::Up, false), Some(9));
+        assert_eq!(selection.anchor, selection.cursor);
+        assert_eq!(nav(&mut selection, Nav::Last, false), Some(99));
+        assert_eq!(nav(&mut selection, Nav::Down, false), Some(99));
+        assert_eq!(nav(&mut selection, Nav::First, false), Some(0));
+        assert_eq!(nav(&mut selection, Nav::Left, false), Some(0));
+        assert_eq!(selection.cursor, Some(cell("o0", "b")));
+    }
+
+    #[test]
+    fn test_keys_and_scroll() {
+        assert_eq!(Nav::parse("ArrowDown", false), Some(Nav::Down));
+        assert_eq!(Nav::parse("Home", false), Some(Nav::Home));
+        assert_eq!(Nav::parse("Home", true), Some(Nav::First));
+        assert_eq!(Nav::parse("a", false), None);
+
+        assert_eq!(scroll_to(5, 10, 20), 5);
+        assert_eq!(scroll_to(15, 10, 20), 10);
+        assert_eq!(scroll_to(30, 10, 20), 11);
+    }
+
+    #[test]
+    fn test_row_key() {
+        let mut schema = Schema {
+            cols: vec![Column {
+                id: "id".to_string(),
+                col_type: ColumnType::Number,
+                size: num_size(),
+                ..Default::default()
+            }],
+            key: "id".to_string(),
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        let mut buf = [0u8; 16];
+        BigEndian::write_f64(&mut buf[0..8], 42.0);
+        BigEndian::write_f64(&mut buf[8..16], 7.0);
+        let ds = DataSource::new(&buf, 8, None);
+        assert_eq!(
+            ds.row_key(1, schema.get_key_col()),
+            Some(RowKey::Number(7.0))
+        );
+        assert_eq!(ds.row_key(1, None), Some(RowKey::Number(1.0)));
+        assert_eq!(ds.row_key(2, None), None);
+
+        schema.key = "missing".to_string();
+        assert!(normalize_schema(&mut schema).is_err());
+    }
+}
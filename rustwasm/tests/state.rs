@@ -0,0 +1,99 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::error::*;
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+    use rustwasm::grid::state::*;
+
+    fn schema(ids: &[&str]) -> Schema {
+        let mut schema = Schema {
+            cols: ids
+                .iter()
+                .map(|id| Column {
+                    id: id.to_string(),
+                    col_type: ColumnType::Number,
+                    size: num_size(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    fn display(schema: &Schema) -> Vec<&str> {
+        schema.get_display_cols().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut schema = schema(&["a", "b", "c"]);
+        schema.move_col("c", 0).unwrap();
+        schema.set_col_width("a", 150.0).unwrap();
+        schema.set_col_hidden("b", true).unwrap();
+        let state = ViewState {
+            query: ViewQuery {
+                sort: vec![SortKey {
+                    col: "a".to_string(),
+                    descending: true,
+                }],
+                filters: vec![Filter {
+                    col: "c".to_string(),
+                    op: FilterOp::Gt,
+                    value: 100.into(),
+                }],
+                group_by: vec!["b".to_string()],
+            },
+            top_index: 42,
+            row_height: 30,
+            ..ViewState::capture(&schema)
+        };
+        let json = state.to_json();
+        assert!(json.contains("\"version\":1"));
+        assert!(json.contains("\"op\":\"gt\""));
+
+        let mut fresh = self::schema(&["a", "b", "c"]);
+        let restored = ViewState::from_json(&json)
+            .unwrap()
+            .restore(&mut fresh)
+            .unwrap();
+        assert_eq!(restored, state);
+        assert_eq!(display(&fresh), ["c", "a", "b"]);
+        assert_eq!(fresh.get_col_by_id("a").unwrap().width, 150.0);
+        assert!(fresh.get_col_by_id("b").unwrap().hidden);
+        assert_eq!(fresh.visible_col_count, 2);
+    }
+
+    #[test]
+    fn test_schema_changed() {
+        let json = r#"{"version":1,"columns":[{"id":"gone","width":80},{"id":"c"},{"id":"a","hidden":true}],
+            "sort":[{"col":"gone"},{"col":"c"}],"group_by":["gone"]}"#;
+        let mut schema = schema(&["a", "b", "c", "new"]);
+        let state = ViewState::from_json(json)
+            .unwrap()
+            .restore(&mut schema)
+            .unwrap();
+        // restored columns first, the rest keep their order
+        assert_eq!(display(&schema), ["c", "a", "b", "new"]);
+        assert!(schema.get_col_by_id("a").unwrap().hidden);
+        assert_eq!(state.columns.len(), 2);
+        assert_eq!(state.query.sort.len(), 1);
+        assert!(state.query.group_by.is_empty());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let err = |json: &str| ViewState::from_json(json).unwrap_err().kind;
+        assert_eq!(err("{}"), ErrorKind::InvalidArgument); // no version
+        assert_eq!(err(r#"{"version":99}"#), ErrorKind::InvalidArgument);
+        assert_eq!(err("not json"), ErrorKind::InvalidArgument);
+        assert_eq!(
+            err(r#"{"version":1,"filters":[{"col":"a","op":"like","value":1}]}"#),
+            ErrorKind::InvalidArgument
+        );
+    }
+}
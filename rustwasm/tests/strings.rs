@@ -0,0 +1,80 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::renderer::*;
+
+    fn col(col_type: ColumnType, size: usize) -> Column {
+        Column {
+            id: "name".to_string(),
+            col_type,
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fixed_string_trims_padding() {
+        let col = col(ColumnType::String, 6);
+        let buf = [b'B', b'T', b'C', 0, 0, 0];
+        let ds = DataSource::new(&buf, 6, None);
+        assert_eq!(ds.get_value_str(0, &col), Some("BTC"));
+    }
+
+    #[test]
+    fn test_fixed_string_drops_cut_codepoint() {
+        // "añb" truncated to 2 bytes cuts 'ñ' (0xC3 0xB1) in half
+        let col = col(ColumnType::String, 2);
+        let buf = [b'a', 0xC3];
+        let ds = DataSource::new(&buf, 2, None);
+        assert_eq!(ds.get_value_str(0, &col), Some("a"));
+    }
+
+    #[test]
+    fn test_var_string() {
+        let col = col(ColumnType::VarString, num_size());
+        let strings = "Bitcoin€Ether".as_bytes();
+        let mut buf = [0u8; 3 * 8];
+        BigEndian::write_u32(&mut buf[0..4], 0);
+        BigEndian::write_u32(&mut buf[4..8], 7);
+        BigEndian::write_u32(&mut buf[8..12], 7);
+        BigEndian::write_u32(&mut buf[12..16], 8);
+        // out of bounds offsets yield no value rather than a panic
+        BigEndian::write_u32(&mut buf[16..20], 100);
+        BigEndian::write_u32(&mut buf[20..24], 2);
+        let ds = DataSource::new(&buf, 8, None).with_strings(strings);
+
+        assert_eq!(ds.get_value_str(0, &col), Some("Bitcoin"));
+        assert_eq!(ds.get_value_str(1, &col), Some("€Ether"));
+        assert_eq!(ds.get_value_str(2, &col), None);
+        assert_eq!(ds.get_value_str(3, &col), None);
+
+        // offset + len past u32::MAX overflows a 32-bit usize
+        BigEndian::write_u32(&mut buf[16..20], u32::MAX);
+        BigEndian::write_u32(&mut buf[20..24], u32::MAX);
+        let ds = DataSource::new(&buf, 8, None).with_strings(strings);
+        assert_eq!(ds.get_value_str(2, &col), None);
+    }
+
+    #[test]
+    fn test_data_window_keeps_var_strings() {
+        let col = col(ColumnType::VarString, num_size());
+        let strings = "Bitcoin€Ether".as_bytes();
+        let mut buf = [0u8; 3 * 8];
+        BigEndian::write_u32(&mut buf[4..8], 7);
+        BigEndian::write_u32(&mut buf[8..12], 7);
+        BigEndian::write_u32(&mut buf[12..16], 8);
+        BigEndian::write_u32(&mut buf[16..20], 100);
+        BigEndian::write_u32(&mut buf[20..24], 2);
+        let ds = DataSource::new(&buf, 8, None).with_strings(strings);
+
+        let window = DataWindow::capture(std::slice::from_ref(&col), &ds, 1..5);
+        assert_eq!((window.first_row, window.row_count()), (1, 2));
+        let ds = window.data_source(None, None);
+        assert_eq!(ds.get_value_str(0, &col), Some("€Ether"));
+        assert_eq!(ds.get_value_str(1, &col), None);
+    }
+}
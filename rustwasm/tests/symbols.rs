@@ -0,0 +1,112 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use byteorder::{BigEndian, ByteOrder};
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::ds::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+    use rustwasm::grid::symbols::*;
+
+    fn strings(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_intern_and_lookup() {
+        let mut table = SymbolTable::from_strings(strings(&["ETH", "BTC"]));
+        assert_eq!(table.intern("BTC"), 1);
+        assert_eq!(table.intern("SOL"), 2);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get(2), Some("SOL"));
+        assert_eq!(table.get(3), None);
+    }
+
+    #[test]
+    fn test_ranks_follow_alphabetical_order() {
+        let mut table = SymbolTable::from_strings(strings(&["ETH", "BTC", "SOL"]));
+        assert_eq!(table.rank(0), Some(1));
+        assert_eq!(table.rank(1), Some(0));
+        assert_eq!(table.rank(2), Some(2));
+        // appending keeps existing indices but updates ranks
+        table.extend(strings(&["ADA"]));
+        assert_eq!(table.get(0), Some("ETH"));
+        assert_eq!(table.rank(3), Some(0));
+        assert_eq!(table.rank(1), Some(1));
+    }
+
+    #[test]
+    fn test_duplicates_share_a_rank() {
+        let mut table = SymbolTable::from_strings(strings(&["LSE", "CME", "LSE"]));
+        assert_eq!(table.get(2), Some("LSE"));
+        assert_eq!(table.intern("LSE"), 0);
+        assert_eq!(
+            (table.rank(0), table.rank(1), table.rank(2)),
+            (Some(1), Some(0), Some(1))
+        );
+        table.intern("AAA");
+        assert_eq!(table.rank(2), Some(2));
+        assert_eq!(table.rank(3), Some(0));
+    }
+
+    #[test]
+    fn test_intern_many() {
+        let mut table = SymbolTable::new();
+        for i in (0..10_000).rev() {
+            table.intern(&format!("S{:05}", i));
+        }
+        assert_eq!(table.rank(0), Some(9_999));
+        assert_eq!(table.rank(9_999), Some(0));
+    }
+
+    #[test]
+    fn test_symbol_column_layout() {
+        let mut schema = Schema {
+            cols: vec![
+                Column {
+                    id: "venue".to_string(),
+                    col_type: ColumnType::Symbol,
+                    size: num_size(),
+                    ..Default::default()
+                },
+                Column {
+                    id: "price".to_string(),
+                    col_type: ColumnType::Number,
+                    size: num_size(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        assert_eq!(schema.cols[0].size, symbol_size());
+        assert_eq!(schema.cols[1].data_offset, symbol_size());
+    }
+
+    #[test]
+    fn test_read_symbols_from_buffer() {
+        let table = SymbolTable::from_strings(strings(&["NYSE", "CME", "LSE"]));
+        let col = Column {
+            id: "venue".to_string(),
+            col_type: ColumnType::Symbol,
+            size: symbol_size(),
+            ..Default::default()
+        };
+        let mut buf = [0u8; 3 * 4];
+        BigEndian::write_u32(&mut buf[0..4], 1);
+        BigEndian::write_u32(&mut buf[4..8], 2);
+        BigEndian::write_u32(&mut buf[8..12], 7);
+        let ds = DataSource::new(&buf, symbol_size(), None).with_symbols(&table);
+
+        assert_eq!(ds.get_value_symbol(0, &col), Some(1));
+        assert_eq!(ds.get_value_str(0, &col), Some("CME"));
+        assert_eq!(ds.get_value_str(1, &col), Some("LSE"));
+        // unknown index renders nothing
+        assert_eq!(ds.get_value_str(2, &col), None);
+
+        let mut rows: Vec<usize> = (0..2).collect();
+        rows.sort_by_key(|&row| ds.get_symbol_rank(row, &col));
+        assert_eq!(rows, [0, 1]);
+    }
+}
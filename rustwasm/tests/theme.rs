@@ -0,0 +1,112 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::theme::*;
+
+    #[test]
+    fn test_presets() {
+        assert!(Theme::dark().validate().is_ok());
+        assert!(Theme::light().validate().is_ok());
+        assert_eq!(Theme::default(), Theme::dark());
+        assert_eq!(Theme::preset("light").unwrap(), Theme::light());
+        assert!(Theme::preset("solarized").is_err());
+        assert_eq!(Theme::dark_ref(), &Theme::dark());
+        assert_eq!(
+            Theme::dark().spark_colors(),
+            ["#ff0000", "#008000", "#232832"]
+        );
+    }
+
+    #[test]
+    fn test_partial_theme() {
+        let theme: Theme = serde_json::from_str(r##"{"gridline": "#444"}"##).unwrap();
+        assert_eq!(theme.gridline, "#444");
+        assert_eq!(theme.background, Theme::dark().background);
+    }
+
+    #[test]
+    fn test_colors() {
+        for color in [
+            "#fff",
+            "#ffff",
+            "#0b0e17",
+            "#0c433899",
+            "rgb(1, 2, 3)",
+            "rgba(255,255,255,0.5)",
+            "rgb(1 2 3 / 50%)",
+            "hsl(120deg, 50%, 50%)",
+            "white",
+            "AliceBlue",
+            "rebeccapurple",
+            "yellowgreen",
+            "transparent",
+        ] {
+            assert!(is_valid_color(color), "{}", color);
+        }
+        for color in [
+            "",
+            "#ff",
+            "#fffff",
+            "#ggg",
+            "rgb(1,2)",
+            "rgb(a,b,c)",
+            "rgb(1,2,3",
+            "cmyk(1,2,3)",
+            "whitish",
+        ] {
+            assert!(!is_valid_color(color), "{}", color);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let theme = Theme {
+            ask_bar: "#ff3b69zz".to_string(),
+            ..Default::default()
+        };
+        let err = theme.validate().unwrap_err();
+        assert!(err.contains("ask_bar"), "{}", err);
+    }
+
+    #[test]
+    fn test_fonts() {
+        let theme = Theme {
+            font: "13px Inter, sans-serif".to_string(),
+            numeric_font: "13px 'JetBrains Mono', monospace".to_string(),
+            ..Default::default()
+        };
+        assert!(theme.validate().is_ok());
+
+        let mut col = Column {
+            col_type: ColumnType::String,
+            ..Default::default()
+        };
+        assert_eq!(theme.cell_font(&col, false), "13px Inter, sans-serif");
+        col.italic = true;
+        assert_eq!(
+            theme.cell_font(&col, true),
+            "italic bold 13px Inter, sans-serif"
+        );
+        col.col_type = ColumnType::Decimal;
+        col.italic = false;
+        col.bold = true;
+        assert_eq!(
+            theme.cell_font(&col, false),
+            "bold 13px 'JetBrains Mono', monospace"
+        );
+
+        for font in ["16px sans-serif", "bold 1.2em serif", "12pt/1.5 Arial"] {
+            assert!(is_valid_font(font), "{}", font);
+        }
+        for font in ["", "sans-serif", "16px", "-2px serif", "big serif"] {
+            assert!(!is_valid_font(font), "{}", font);
+        }
+        let bad = Theme {
+            header_font: "bold".to_string(),
+            ..Default::default()
+        };
+        assert!(bad.validate().unwrap_err().contains("header_font"));
+    }
+}
@@ -0,0 +1,90 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::locale::*;
+    use rustwasm::grid::pointer::*;
+    use rustwasm::grid::renderer::*;
+    use rustwasm::grid::schema::*;
+    use rustwasm::grid::tooltip::*;
+
+    fn schema() -> Schema {
+        let mut schema = Schema {
+            cols: vec![
+                Column {
+                    id: "sym".to_string(),
+                    col_type: ColumnType::Number,
+                    size: num_size(),
+                    description: "Instrument symbol".to_string(),
+                    ..Default::default()
+                },
+                Column {
+                    id: "px".to_string(),
+                    col_type: ColumnType::Number,
+                    size: num_size(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        normalize_schema(&mut schema).unwrap();
+        schema
+    }
+
+    // two 100px columns, 30px header and rows
+    fn header() -> HeaderLayout {
+        HeaderLayout {
+            top: 0.0,
+            bottom: 30.0,
+            edges: vec![0.0, 100.0, 200.0],
+            ids: vec!["sym".to_string(), "px".to_string()],
+            row_height: 30.0,
+        }
+    }
+
+    fn cells(row: usize, col: &Column) -> Option<String> {
+        let rows = [["Bitcoin perpetual swap", "1.5"], ["ETH", ""]];
+        let i = if col.id == "sym" { 0 } else { 1 };
+        rows.get(row).map(|cells| cells[i].to_string())
+    }
+
+    #[test]
+    fn test_header() {
+        let tip = tooltip_at(&header(), &schema(), cells, 20.0, 10.0).unwrap();
+        assert_eq!(tip.text, "Instrument symbol");
+        assert_eq!(
+            (tip.x, tip.y, tip.width, tip.height),
+            (0.0, 0.0, 100.0, 30.0)
+        );
+        // no description
+        assert_eq!(tooltip_at(&header(), &schema(), cells, 120.0, 10.0), None);
+    }
+
+    #[test]
+    fn test_cells() {
+        let tip = tooltip_at(&header(), &schema(), cells, 20.0, 45.0).unwrap();
+        assert_eq!(tip.text, "Bitcoin perpetual swap");
+        assert_eq!(
+            (tip.x, tip.y, tip.width, tip.height),
+            (0.0, 30.0, 100.0, 30.0)
+        );
+        let tip = tooltip_at(&header(), &schema(), cells, 150.0, 65.0);
+        assert_eq!(tip, None); // empty cell
+        assert_eq!(tooltip_at(&header(), &schema(), cells, 20.0, 95.0), None); // below the data
+        assert_eq!(tooltip_at(&header(), &schema(), cells, 250.0, 45.0), None); // right of the columns
+    }
+
+    #[test]
+    fn test_sparkline_summary() {
+        assert_eq!(
+            sparkline_summary(&[2.0, 1.0, 3.5, 2.25], 2, &EN_US),
+            "min 1.00 · max 3.50 · last 2.25"
+        );
+        assert_eq!(
+            sparkline_summary(&[1234.5], 1, &DE_DE),
+            "min 1234,5 · max 1234,5 · last 1234,5"
+        );
+        assert_eq!(sparkline_summary(&[], 2, &EN_US), "");
+    }
+}
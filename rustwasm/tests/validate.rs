@@ -0,0 +1,105 @@
+#![allow(dead_code)]
+
+#[cfg(test)]
+mod tests {
+    use rustwasm::grid::column::*;
+    use rustwasm::grid::schema::*;
+    use rustwasm::grid::validate::*;
+
+    fn col(id: &str, col_type: ColumnType, size: usize) -> Column {
+        Column {
+            id: id.to_string(),
+            col_type,
+            size,
+            ..Default::default()
+        }
+    }
+
+    fn paths(problems: &[SchemaProblem]) -> Vec<&str> {
+        problems.iter().map(|p| p.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_schema() {
+        let schema = Schema {
+            cols: vec![
+                col("symbol", ColumnType::String, 10),
+                col("price", ColumnType::Number, 8),
+            ],
+            ..Default::default()
+        };
+        assert!(validate_schema(&schema, &ValidateOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let mut time = col("time", ColumnType::Timestamp, 8);
+        time.format = "%H:%M:%Q".to_string();
+        let mut price = col("price", ColumnType::Number, 8);
+        price.align = "middle".to_string();
+        let schema = Schema {
+            cols: vec![
+                col("symbol", ColumnType::String, 0),
+                price,
+                col("price", ColumnType::Number, 8),
+                time,
+                col("spark", ColumnType::Sparkline, 10),
+                col("", ColumnType::Number, 4),
+            ],
+            ..Default::default()
+        };
+        let problems = validate_schema(
+            &schema,
+            &ValidateOptions {
+                has_sparks: Some(false),
+                required_cols: &[CUM_SIZE_COL_ID],
+            },
+        );
+        assert_eq!(
+            paths(&problems),
+            [
+                "$.cols[0].size",
+                "$.cols[1].align",
+                "$.cols[2].id",
+                "$.cols[3].format",
+                "$.cols[4].col_type",
+                "$.cols[5].id",
+                "$.cols[5].size",
+                "$.cols",
+            ]
+        );
+        assert_eq!(problems[2].column.as_deref(), Some("price"));
+        assert!(problems[2].message.contains("$.cols[1]"));
+        assert_eq!(problems[7].column.as_deref(), Some("cumSize"));
+    }
+
+    #[test]
+    fn test_all_hidden() {
+        let mut a = col("a", ColumnType::Number, 8);
+        a.hidden = true;
+        let schema = Schema {
+            cols: vec![a],
+            ..Default::default()
+        };
+        let problems = validate_schema(&schema, &ValidateOptions::default());
+        assert_eq!(paths(&problems), ["$.cols"]);
+        assert_eq!(problems[0].message, "all columns are hidden");
+    }
+
+    #[test]
+    fn test_date_formats() {
+        assert!(is_valid_date_format("%Y-%m-%d %H:%M:%S%.3f"));
+        assert!(is_valid_date_format("%r"));
+        assert!(!is_valid_date_format("%Q"));
+        assert!(!is_valid_date_format("%"));
+    }
+
+    #[test]
+    fn test_sparks_unknown_not_reported() {
+        let schema = Schema {
+            cols: vec![col("spark", ColumnType::Sparkline, 10)],
+            ..Default::default()
+        };
+        assert!(validate_schema(&schema, &ValidateOptions::default()).is_empty());
+    }
+}
@@ -16,7 +16,7 @@ wasm_bindgen_test_configure!(run_in_browser);
 
 #[cfg(test)]
 pub fn init_grid() -> Grid {
-    let grid = Grid::new("canvas".to_string(), &JsValue::NULL);
+    let grid = Grid::new("canvas".to_string(), &JsValue::NULL).unwrap();
     grid
 }
 
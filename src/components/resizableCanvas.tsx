@@ -1,4 +1,4 @@
-import { useCallback, useRef } from "react";
+import { useCallback, useEffect, useRef } from "react";
 import "./styles.scss";
 import React from "react";
 import { useWheelEvent, useResizeObserver, useMouseLeaveEvent } from "../hooks";
@@ -7,15 +7,25 @@ export interface ResizableCanvasProps {
   id: string;
   rowCount?: number;
   rowHeight?: number;
+  scrollTop?: number; // top row set by the host, e.g. after keyboard navigation
   onResize: ({ width, height }: { width: number; height: number }) => void;
   onScroll?: ({ top, left }: { top?: number; left?: number }) => void;
+  onPointer?: (kind: PointerKind, e: React.PointerEvent<HTMLCanvasElement>) => void;
+  onKeyDown?: (e: React.KeyboardEvent<HTMLCanvasElement>) => void;
+  onDoubleClick?: (e: React.MouseEvent<HTMLCanvasElement>) => void;
 }
 
+export type PointerKind = "down" | "move" | "up" | "cancel";
+
 export const ResizableCanvas: React.FC<ResizableCanvasProps> = (props: ResizableCanvasProps) => {
-  const { id, onResize, onScroll } = props;
+  const { id, onResize, onScroll, onPointer, onKeyDown, onDoubleClick } = props;
   const div = useRef<HTMLDivElement>(null);
   const canvas = useRef<HTMLCanvasElement>(null);
-  let top = 0;
+  const top = useRef(0);
+
+  useEffect(() => {
+    if (props.scrollTop !== undefined) top.current = props.scrollTop;
+  }, [props.scrollTop]);
 
   const resized = () => {
     if (div.current && canvas.current) {
@@ -27,8 +37,8 @@ export const ResizableCanvas: React.FC<ResizableCanvasProps> = (props: Resizable
     (e: WheelEvent) => {
       if (e.deltaY) {
         const delta = Math.sign(e.deltaY) / 2;
-        top = Math.max(0, Math.min(top + delta, (props.rowCount || 0) - 1));
-        props.onScroll?.({ top });
+        top.current = Math.max(0, Math.min(top.current + delta, (props.rowCount || 0) - 1));
+        props.onScroll?.({ top: top.current });
       }
     },
     [canvas, onScroll, props.rowCount]
@@ -36,8 +46,8 @@ export const ResizableCanvas: React.FC<ResizableCanvasProps> = (props: Resizable
 
   const mouseLeft = useCallback(
     (e: MouseEvent) => {
-      top = 0;
-      props.onScroll?.({ top });
+      top.current = 0;
+      props.onScroll?.({ top: top.current });
     },
     [canvas, onScroll]
   );
@@ -48,7 +58,17 @@ export const ResizableCanvas: React.FC<ResizableCanvasProps> = (props: Resizable
 
   return (
     <div ref={div} className={"canvas-wrapper"}>
-      <canvas ref={canvas} id={id}></canvas>
+      <canvas
+        ref={canvas}
+        id={id}
+        tabIndex={0}
+        onKeyDown={onKeyDown}
+        onDoubleClick={onDoubleClick}
+        onPointerDown={(e) => onPointer?.("down", e)}
+        onPointerMove={(e) => onPointer?.("move", e)}
+        onPointerUp={(e) => onPointer?.("up", e)}
+        onPointerCancel={(e) => onPointer?.("cancel", e)}
+      ></canvas>
     </div>
   );
 };
@@ -1,13 +1,14 @@
 /// <reference path="./../../rustwasm/pkg/rustwasm.d.ts"/>
-import React, { useCallback } from "react";
+import React, { useCallback, useEffect, useRef } from "react";
 import { useState } from "react";
 import { useAnimationFrame } from "../hooks/useAnimationFrame";
 import { useRustWasm } from "../hooks";
-import { ResizableCanvas } from "./resizableCanvas";
+import { PointerKind, ResizableCanvas } from "./resizableCanvas";
 import "./styles.scss";
 import classnames from "classnames";
 import { generateTradeData, tradeSchema } from "../feeders";
 import { Header } from "./header";
+import { ClipFormat, GridEventKind, ScrollChangeEvent } from "../core";
 
 const UPDATE_FREQ = 100;
 
@@ -20,11 +21,15 @@ export function TapeComponent(props: TapeComponentProps) {
   const [rowCount, setRowCount] = useState<number>(0);
   const [freq, setFreq] = useState<number>(UPDATE_FREQ);
   const [grid, setGrid] = useState<any>(null);
+  const [scrollTop, setScrollTop] = useState<number>(0);
   const [size, setSize] = useState<{ width?: number; height?: number }>({
     width: 0,
     height: 0
   });
 
+  // buffers of the last render, for copying the selection
+  const last = useRef<{ data: Int8Array; width: number }>();
+
   const wasm = useRustWasm();
   if (wasm && !grid) {
     setGrid(wasm.Grid.new(id, tradeSchema));
@@ -35,17 +40,78 @@ export function TapeComponent(props: TapeComponentProps) {
       const [data, data_width, count] = generateTradeData();
       setRowCount(count);
       grid.render(data, data_width, 0, 0, size.width, size.height);
+      last.current = { data, width: data_width };
     }
   };
 
   const onResize = ({ width, height }: { width: number; height: number }) => {
     if (size.width !== width || size.height !== height) {
       grid?.set_top_index(0);
+      setScrollTop(0);
       setSize({ width, height });
     }
   };
 
-  const onScroll = useCallback(({ top, left }) => grid?.set_top_index(top), [grid]);
+  const onScroll = useCallback(
+    ({ top, left }) => {
+      grid?.set_top_index(top);
+      setScrollTop(top);
+    },
+    [grid]
+  );
+
+  // keyboard navigation scrolls inside the grid, keep the canvas scroll in step
+  useEffect(() => {
+    if (!grid) return;
+    grid.on(GridEventKind.ScrollChange, (e: ScrollChangeEvent) => setScrollTop(e.top_index));
+    return () => grid.on(GridEventKind.ScrollChange, undefined);
+  }, [grid]);
+
+  // header resize and reorder and cell selection, the grid hit-tests and keeps the layout
+  const onPointer = useCallback(
+    (kind: PointerKind, e: React.PointerEvent<HTMLCanvasElement>) => {
+      if (!grid) return;
+      const { offsetX: x, offsetY: y } = e.nativeEvent;
+      const canvas = e.currentTarget;
+      switch (kind) {
+        case "down":
+          if (grid.pointer_down(x, y, e.shiftKey, e.ctrlKey || e.metaKey)) canvas.setPointerCapture(e.pointerId);
+          break;
+        case "move":
+          grid.pointer_move(x, y);
+          canvas.title = grid.tooltip_at(x, y)?.text ?? "";
+          break;
+        case "up":
+          grid.pointer_up(x, y);
+          break;
+        case "cancel":
+          grid.pointer_cancel();
+          break;
+      }
+      canvas.style.cursor = grid.get_cursor(x, y);
+    },
+    [grid]
+  );
+
+  const onKeyDown = useCallback(
+    (e: React.KeyboardEvent<HTMLCanvasElement>) => {
+      const ctrl = e.ctrlKey || e.metaKey;
+      if (ctrl && e.key === "c") {
+        const text = last.current
+          ? grid?.copy_selection(last.current.data, new Uint8Array(0), last.current.width, ClipFormat.Tsv, false, false)
+          : "";
+        navigator.clipboard?.writeText(text ?? "");
+      } else if (grid?.key_down(e.key, e.shiftKey, ctrl)) {
+        e.preventDefault();
+      }
+    },
+    [grid]
+  );
+
+  const onDoubleClick = useCallback(
+    (e: React.MouseEvent<HTMLCanvasElement>) => grid?.double_click(e.nativeEvent.offsetX, e.nativeEvent.offsetY),
+    [grid]
+  );
 
   useAnimationFrame(freq, tick);
 
@@ -56,8 +122,12 @@ export function TapeComponent(props: TapeComponentProps) {
         id={id}
         onResize={onResize}
         onScroll={onScroll}
+        onPointer={onPointer}
+        onKeyDown={onKeyDown}
+        onDoubleClick={onDoubleClick}
         rowCount={rowCount}
         rowHeight={grid?.row_height}
+        scrollTop={scrollTop}
       />
     </div>
   );
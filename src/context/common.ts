@@ -1,42 +1,121 @@
-import { Column, ColumnType, NUM_SIZE } from "../core";
+import {
+  colDataSize,
+  Column,
+  ColumnType,
+  DECIMAL_NULL,
+  HEADER_SIZE,
+  NullMode,
+  NUM_SIZE,
+  Schema,
+  validitySize,
+  writeHeader
+} from "../core";
 
 export interface RawData {
   [id: string]: any;
 }
 
+// Rows start after the buffer header when the schema has one, each row with its
+// validity bitmap. null/undefined values are written as nulls of the schema null_mode.
 export function fill<T extends RawData>(
   buffer: ArrayBuffer,
   data: T[],
   totalSize: number,
-  columns: Column[],
-  getter?: (item: T, col: Column) => number | string
+  schema: Schema,
+  getter?: (item: T, col: Column) => number | string | bigint | null | undefined,
+  strings?: StringBuffer
 ): Int8Array {
-  const array = new Int8Array(buffer, 0, data.length * totalSize);
+  const base = schema.buffer_header ? HEADER_SIZE : 0;
+  const array = new Int8Array(buffer, 0, base + data.length * totalSize);
   const view = new DataView(array.buffer);
-  for (let row = 0, offset = 0; row < data.length; row++) {
+  const nullMode = schema.null_mode ?? NullMode.None;
+  const bitmap = validitySize(schema);
+  if (schema.buffer_header) {
+    writeHeader(view, schema, data.length);
+  }
+  for (let row = 0; row < data.length; row++) {
+    const start = base + row * totalSize;
+    let offset = start + bitmap;
     try {
+      array.fill(0, start, start + totalSize);
       const item = data[row];
-      for (const col of columns) {
+      schema.cols.forEach((col, index) => {
         const v = getter?.(item, col) ?? item[col.id];
-        if (col.col_type === ColumnType.String || col.col_type===ColumnType.Sparkline) {
-          if (v) {
-            const s = new TextEncoder().encode(
-              ((v as string) ?? "").substring(0, col.size ?? NUM_SIZE)
-            );
+        const isNull = v === null || v === undefined;
+        if (!isNull && nullMode === NullMode.Bitmap) {
+          array[start + (index >> 3)] |= 1 << (index & 7);
+        }
+        if (col.col_type === ColumnType.String || col.col_type === ColumnType.Sparkline) {
+          if (!isNull) {
+            const s = new TextEncoder().encode(String(v).substring(0, col.size ?? NUM_SIZE));
             array.set(s, offset);
           }
-          offset += col.size ?? NUM_SIZE;
+        } else if (col.col_type === ColumnType.VarString) {
+          if (!isNull && strings) {
+            const [first, length] = strings.append(String(v));
+            view.setUint32(offset, first);
+            view.setUint32(offset + 4, length);
+          }
+        } else if (col.col_type === ColumnType.Symbol) {
+          if (!isNull) {
+            view.setUint32(offset, v as number);
+          }
+        } else if (col.col_type === ColumnType.Decimal) {
+          if (!isNull) {
+            view.setBigInt64(offset, toScaledBigInt(v as number | string | bigint, col.scale ?? 0));
+          } else if (nullMode === NullMode.Sentinel) {
+            view.setBigInt64(offset, DECIMAL_NULL);
+          }
         } else {
-          if (v) {
+          if (!isNull) {
             console.assert(typeof v === "number");
-            view.setFloat64(offset, (v as number) ?? 0);
+            view.setFloat64(offset, v as number);
+          } else if (nullMode === NullMode.Sentinel) {
+            view.setFloat64(offset, NaN);
           }
-          offset += NUM_SIZE;
         }
-      }
+        offset += colDataSize(col);
+      });
     } catch (error) {
       console.error(`${error}, offset=${offset}, buffer-size=${array.length}`);
     }
   }
   return array;
 }
+
+// decimal strings are scaled without going through float, e.g. ("0.1", 8) => 10000000
+export function toScaledBigInt(v: number | string | bigint, scale: number): bigint {
+  if (typeof v === "bigint") {
+    return v;
+  }
+  const [int, frac = ""] = (typeof v === "number" ? v.toFixed(scale) : v).split(".");
+  return BigInt(int + frac.padEnd(scale, "0").substring(0, scale));
+}
+
+// side buffer for VarString columns: rows keep [offset, length], the bytes live here
+export class StringBuffer {
+  private bytes = new Uint8Array(1024);
+  private length = 0;
+  private encoder = new TextEncoder();
+
+  append(s: string): [number, number] {
+    const encoded = this.encoder.encode(s);
+    if (this.length + encoded.length > this.bytes.length) {
+      const grown = new Uint8Array(Math.max(this.bytes.length * 2, this.length + encoded.length));
+      grown.set(this.bytes.subarray(0, this.length));
+      this.bytes = grown;
+    }
+    const start = this.length;
+    this.bytes.set(encoded, start);
+    this.length += encoded.length;
+    return [start, encoded.length];
+  }
+
+  clear() {
+    this.length = 0;
+  }
+
+  data(): Uint8Array {
+    return this.bytes.subarray(0, this.length);
+  }
+}
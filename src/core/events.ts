@@ -0,0 +1,32 @@
+// Grid.on(kind, callback) and DOB.on(kind, callback) payloads. Only changes made
+// inside the canvas are reported, not the ones made through setters. Callbacks run
+// as microtasks once the grid call that raised the event has returned.
+import { Selection, RowKey } from "./selection";
+import { ColumnState, SortKey } from "./state";
+
+export enum GridEventKind {
+  CellClick = 0,
+  CellDoubleClick,
+  SelectionChange,
+  SortChange, // header click: ascending, descending, unsorted
+  ScrollChange,
+  ColumnLayoutChange
+}
+
+export interface CellEvent {
+  row: RowKey;
+  col: string; // column id
+  value: number | string | number[] | null; // raw value as stored, decimals as exact strings, null for nulls
+  side?: "bid" | "ask"; // DOB only
+}
+
+export type SelectionChangeEvent = Selection;
+export interface SortChangeEvent {
+  sort: SortKey[]; // the host sorts the data
+}
+export interface ScrollChangeEvent {
+  top_index: number;
+}
+export interface ColumnLayoutChangeEvent {
+  columns: ColumnState[]; // display order
+}
@@ -0,0 +1,41 @@
+// Formats of Grid.export_rows, which returns UTF-8 bytes for a Blob part.
+export enum ExportFormat {
+  Csv = 0, // RFC 4180, CRLF line ends
+  JsonLines // one object per row keyed by column id
+}
+
+export const exportMimeType = (format: ExportFormat): string =>
+  format === ExportFormat.Csv ? "text/csv" : "application/x-ndjson";
+
+export interface RowExporter {
+  export_rows(
+    data: Int8Array | Uint8Array,
+    strings: Int8Array | Uint8Array,
+    data_width: number,
+    format: ExportFormat,
+    raw: boolean,
+    header: boolean,
+    start: number,
+    end: number
+  ): Uint8Array;
+}
+
+// Pulls the rows in chunks so the whole file is never one wasm allocation.
+export function exportBlob(
+  grid: RowExporter,
+  data: Int8Array | Uint8Array,
+  strings: Int8Array | Uint8Array,
+  dataWidth: number,
+  format: ExportFormat,
+  raw = false,
+  header = true,
+  chunkRows = 10000
+): Blob {
+  const parts: Uint8Array[] = [];
+  for (let start = 0; ; start += chunkRows) {
+    const part = grid.export_rows(data, strings, dataWidth, format, raw, header, start, start + chunkRows);
+    if (part.length === 0) break;
+    parts.push(part);
+  }
+  return new Blob(parts, { type: exportMimeType(format) });
+}
@@ -1 +1,7 @@
-export * from './schema';
\ No newline at end of file
+export * from './schema';
+export * from './theme';
+export * from './state';
+export * from './tooltip';
+export * from './selection';
+export * from './export';
+export * from './events';
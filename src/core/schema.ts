@@ -1,5 +1,10 @@
 export interface Schema {
   cols: Column[];
+  key?: string; // id of the column identifying a row, keeps the selection across updates
+  order?: number[]; // display order as indices into cols, the buffer layout follows cols
+  null_mode?: NullMode;
+  null_text?: string;
+  buffer_header?: boolean; // buffers start with writeHeader()
 }
 export interface Column {
   id: string;
@@ -7,10 +12,33 @@ export interface Column {
   col_type: ColumnType;
   size?: number;
   precision?: number;
+  scale?: number; // Decimal: implied fraction digits of the stored int64
+  group?: string[]; // header groups, outermost first, e.g. ["Bid"]
   hidden?: boolean;
+  width?: number; // px, 0 or missing shares the remaining space; set by Grid.auto_fit_columns
+  overflow?: Overflow; // text wider than the cell; numbers always show ###
+  bold?: boolean;
+  italic?: boolean;
   highlight?: boolean;
   suppress_zero?: boolean;
-  format?: string;
+  format?: string; // dates: chrono strftime, %.3f for millis, or "relative"; numbers: [+|(][,][.precision][f|g|%|b|a]
+  prefix?: string;
+  suffix?: string;
+  price?: PriceFormat; // overrides format for Number columns
+  epoch_unit?: EpochUnit; // dates and durations, default Millis
+  timezone?: string; // dates: "" local, "UTC", "+05:30" or "America/New_York"
+}
+export enum FitMode {
+  Visible = 0, // rows in view
+  Sample // up to 1000 rows across the data
+}
+
+export interface PriceFormat {
+  tick_size?: number;
+  fraction?: number; // 32 renders 99-16
+  sub_fraction?: number; // 2, 4 or 8
+  big_figure_digits?: number;
+  trailing_digits?: number;
 }
 
 export enum ColumnType {
@@ -20,16 +48,80 @@ export enum ColumnType {
   Date,
   DateTime,
   Timestamp,
-  Sparkline
+  Sparkline,
+  Decimal,
+  Symbol, // u32 index into the string table registered with Grid.set_symbols
+  VarString, // u32 offset + u32 length into the strings buffer passed to render_with_strings
+  Duration // f64 in epoch_unit units, format "compact" (1.25ms) or "human"
+}
+
+export enum EpochUnit {
+  Millis = 0,
+  Seconds,
+  Micros,
+  Nanos
+}
+
+export enum Overflow {
+  Ellipsis = 0,
+  Clip
+}
+
+export enum NullMode {
+  None = 0,
+  Sentinel, // NaN for numbers/dates, int64 min for decimals
+  Bitmap // validity bitmap at the start of each row, one bit per column
 }
 
 export const NUM_SIZE = 8;
+export const SYMBOL_SIZE = 4;
+export const DECIMAL_NULL = BigInt("-9223372036854775808"); // NullMode.Sentinel for Decimal columns
+export function validitySize(schema: Schema): number {
+  return schema?.null_mode === NullMode.Bitmap ? Math.ceil(schema.cols.length / 8) : 0;
+}
+
+export function colDataSize(c: Column): number {
+  return c.col_type === ColumnType.String || c.col_type === ColumnType.Sparkline
+    ? c.size ?? NUM_SIZE
+    : c.col_type === ColumnType.Symbol
+    ? SYMBOL_SIZE
+    : NUM_SIZE;
+}
+
 export function calcDataWidth(schema: Schema): number {
-  return schema?.cols.reduce(
-    (p, c) =>
-      c.col_type === ColumnType.String || c.col_type === ColumnType.Sparkline
-        ? (p += c.size ?? NUM_SIZE)
-        : (p += NUM_SIZE),
-    0
-  );
+  return schema?.cols.reduce((p, c) => p + colDataSize(c), validitySize(schema));
+}
+
+// must match layout.rs
+export const HEADER_MAGIC = 0x47524944; // "GRID"
+export const LAYOUT_VERSION = 1;
+export const HEADER_SIZE = 16;
+
+// FNV-1a over column types, offsets, sizes and scales, same as layout::schema_hash
+export function schemaHash(schema: Schema): number {
+  let hash = 0x811c9dc5;
+  const feed = (v: number) => {
+    for (const shift of [24, 16, 8, 0]) {
+      hash ^= (v >>> shift) & 0xff;
+      hash = Math.imul(hash, 0x01000193) >>> 0;
+    }
+  };
+  let offset = validitySize(schema);
+  feed(offset);
+  for (const c of schema.cols) {
+    const size = colDataSize(c);
+    feed(c.col_type);
+    feed(offset);
+    feed(size);
+    feed(c.scale ?? 0);
+    offset += size;
+  }
+  return hash >>> 0;
+}
+
+export function writeHeader(view: DataView, schema: Schema, rowCount: number) {
+  view.setUint32(0, HEADER_MAGIC);
+  view.setUint32(4, LAYOUT_VERSION);
+  view.setUint32(8, rowCount);
+  view.setUint32(12, schemaHash(schema));
 }
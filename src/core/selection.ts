@@ -0,0 +1,19 @@
+// Returned by Grid.get_selection. Rows are identified by the schema key column value,
+// or by buffer index when the schema has no key.
+export type RowKey = number | string;
+export interface CellRef {
+  row: RowKey;
+  col: string; // column id
+}
+export interface Selection {
+  cursor?: CellRef;
+  anchor?: CellRef; // the range spans anchor to cursor
+  rows: RowKey[]; // whole rows added with ctrl-click
+}
+
+// Grid.copy_selection(data, strings, data_width, format, raw, header)
+export enum ClipFormat {
+  Tsv = 0, // pastes into Excel as cells
+  Csv, // RFC 4180
+  Html
+}
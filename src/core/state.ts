@@ -0,0 +1,26 @@
+// Document returned by Grid.get_state and accepted by Grid.set_state (as JSON).
+// Sort, filters and group_by are applied by the feeder, the grid only keeps them.
+export interface ViewState {
+  version: number; // 1
+  columns?: ColumnState[]; // display order
+  sort?: SortKey[];
+  filters?: Filter[];
+  group_by?: string[]; // column ids
+  top_index?: number;
+  row_height?: number; // 0 - from the theme fonts
+}
+export interface ColumnState {
+  id: string;
+  width?: number;
+  hidden?: boolean;
+}
+export interface SortKey {
+  col: string;
+  descending?: boolean;
+}
+export type FilterOp = "eq" | "ne" | "lt" | "le" | "gt" | "ge" | "contains";
+export interface Filter {
+  col: string;
+  op: FilterOp;
+  value: string | number | boolean | null;
+}
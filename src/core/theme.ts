@@ -0,0 +1,23 @@
+// Colors passed to Grid.set_theme / DOB.set_theme, any CSS color.
+// Missing fields keep the dark preset; "dark" and "light" select a preset.
+export interface Theme {
+  background?: string;
+  client_background?: string;
+  text?: string;
+  header_text?: string;
+  highlight_text?: string;
+  highlight_background?: string;
+  gridline?: string;
+  selection_background?: string;
+  focus?: string; // outline of the cursor cell
+  bid_bar?: string;
+  ask_bar?: string;
+  spark_up?: string;
+  spark_down?: string;
+  spark_flat?: string;
+  font?: string; // CSS font shorthand, e.g. "16px sans-serif"
+  header_font?: string;
+  numeric_font?: string; // Number, Decimal and Duration cells
+}
+
+export type ThemeSpec = "dark" | "light" | Theme;
@@ -0,0 +1,8 @@
+// Returned by Grid.tooltip_at, the rect is in canvas pixels.
+export interface Tooltip {
+  text: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
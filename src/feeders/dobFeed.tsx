@@ -74,7 +74,7 @@ export function generateDOBData(): {
 
   function toBuffer(buffer: ArrayBuffer, data: Quote[]) {
     let sum = 0;
-    return fill<Quote>(buffer, data, data_width, dobSchema.cols, (data: Quote, col: Column) =>
+    return fill<Quote>(buffer, data, data_width, dobSchema, (data: Quote, col: Column) =>
       col.id === CUM_SIZE_COL_ID ? (sum += data.size) : data[col.id]
     );
   }
@@ -92,7 +92,7 @@ export async function generatePythData(): Promise<[Int8Array, number, number]> {
     buffer = new ArrayBuffer(totalSize);
   }
 
-  const array = fill<PythQuote>(buffer, quotes, size, pythSchema.cols, (data: PythQuote, col: Column) => {
+  const array = fill<PythQuote>(buffer, quotes, size, pythSchema, (data: PythQuote, col: Column) => {
     switch (col.id) {
       case "max_supply":
         return (data.max_supply ?? 0) / 1000000;
@@ -66,7 +66,7 @@ export function generateTradeData(): [Int8Array, number, number] {
     trade_buffer, //
     raw_data,
     data_width,
-    tradeSchema.cols
+    tradeSchema
   );
   return [array, data_width, raw_data.length];
 }